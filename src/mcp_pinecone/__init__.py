@@ -1,9 +1,12 @@
 from . import server
+from .config import load_config
 import asyncio
 
 
 def main():
-    asyncio.run(server.main())
+    # Configuration is only read here so importing the package has no side effects
+    config = load_config()
+    asyncio.run(server.main(config))
 
 
 # Optionally expose other important items at package level
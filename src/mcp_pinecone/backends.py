@@ -0,0 +1,295 @@
+import base64
+import binascii
+import json
+import os
+import re
+from typing import Any, Dict, List, Optional, Protocol, Union
+
+from .blobs import BlobStore
+from .config import ServerConfig
+from .constants import (
+    DEFAULT_LIST_LIMIT,
+    DELETE_BATCH_SIZE,
+    FETCH_BATCH_SIZE,
+    VECTOR_BACKENDS,
+)
+from .embeddings import create_embedder
+from .sparse import create_sparse_encoder
+from .pinecone import PineconeRecord
+
+# Chunk IDs are {doc_id}#chunk{n}
+CHUNK_ID_PATTERN = re.compile(r"(?P<doc_id>.*)#chunk\d+")
+
+
+class VectorStore(Protocol):
+    """
+    The operations the MCP server needs from a vector store.
+    Responses are plain dicts shaped like Pinecone's REST responses.
+    """
+
+    def generate_embeddings(self, text: str) -> List[float]: ...
+
+    def generate_embeddings_batch(self, texts: List[str]) -> List[List[float]]: ...
+
+    def upsert_records(
+        self,
+        records: List[PineconeRecord],
+        namespace: Optional[str] = None,
+    ) -> Dict[str, Any]: ...
+
+    def search_records(
+        self,
+        query: Union[str, List[float]],
+        top_k: int = 10,
+        namespace: Optional[str] = None,
+        filter: Optional[Dict] = None,
+        include_metadata: bool = True,
+        alpha: Optional[float] = None,
+    ) -> Dict[str, Any]: ...
+
+    def fetch_records(
+        self, ids: List[str], namespace: Optional[str] = None
+    ) -> Dict[str, Any]: ...
+
+    def delete_records(
+        self, ids: List[str], namespace: Optional[str] = None
+    ) -> Dict[str, Any]: ...
+
+    def update_metadata(
+        self, record_id: str, metadata: Dict[str, Any], namespace: Optional[str] = None
+    ) -> Dict[str, Any]: ...
+
+    def list_records(
+        self,
+        prefix: Optional[str] = None,
+        limit: int = 100,
+        namespace: Optional[str] = None,
+        pagination_token: Optional[str] = None,
+    ) -> Dict[str, Any]: ...
+
+    def describe_index(self) -> Dict[str, Any]: ...
+
+
+def list_record_ids(
+    store: VectorStore, prefix: Optional[str] = None, namespace: Optional[str] = None
+) -> List[str]:
+    """
+    List the IDs of all records, following pagination to the last page.
+
+    Parameters:
+        store: The vector store.
+        prefix: Optional prefix to filter records by.
+        namespace: Optional namespace to list records from.
+
+    Returns:
+        List[str]: The record IDs.
+    """
+    ids = []
+    pagination_token = None
+    while True:
+        page = store.list_records(
+            prefix=prefix, namespace=namespace, pagination_token=pagination_token
+        )
+        ids.extend(record["id"] for record in page.get("vectors", []))
+        pagination_token = page.get("pagination_token")
+        if not pagination_token:
+            return ids
+
+
+def fetch_metadata(
+    store: VectorStore, ids: List[str], namespace: Optional[str] = None
+) -> Dict[str, Dict[str, Any]]:
+    """
+    Fetch the metadata of many records, in batches the fetch API accepts.
+
+    Parameters:
+        store: The vector store.
+        ids: The record IDs.
+        namespace: Optional namespace to fetch from.
+
+    Returns:
+        Dict[str, Dict[str, Any]]: Metadata keyed by ID, for the records found.
+    """
+    metadata = {}
+    for start in range(0, len(ids), FETCH_BATCH_SIZE):
+        response = store.fetch_records(
+            ids[start : start + FETCH_BATCH_SIZE], namespace=namespace
+        )
+        for record_id, vector in response.get("vectors", {}).items():
+            metadata[record_id] = vector.get("metadata") or {}
+    return metadata
+
+
+def record_document_id(record_id: str) -> str:
+    """
+    The ID of the document a chunk belongs to, the record ID itself for
+    records not upserted as chunks.
+    """
+    match = CHUNK_ID_PATTERN.fullmatch(record_id)
+    return match.group("doc_id") if match else record_id
+
+
+def list_chunk_ids(
+    store: VectorStore, doc_id: str, namespace: Optional[str] = None
+) -> List[str]:
+    """
+    List the IDs of a document's chunks.
+
+    Parameters:
+        store: The vector store.
+        doc_id: The document ID the chunks were upserted with.
+        namespace: Optional namespace to list chunks from.
+
+    Returns:
+        List[str]: The chunk IDs.
+    """
+    # The prefix also matches documents such as {doc_id}#v2, whose chunks
+    # are {doc_id}#v2#chunk{n}, so keep only this document's own chunks
+    record_ids = list_record_ids(store, prefix=f"{doc_id}#", namespace=namespace)
+    return [
+        record_id
+        for record_id in record_ids
+        if record_document_id(record_id) == doc_id
+    ]
+
+
+def _encode_cursor(pagination_token: Optional[str], skip: int) -> str:
+    cursor = json.dumps({"token": pagination_token, "skip": skip})
+    return base64.urlsafe_b64encode(cursor.encode("utf-8")).decode("ascii")
+
+
+def _decode_cursor(cursor: str) -> Dict[str, Any]:
+    try:
+        decoded = json.loads(base64.urlsafe_b64decode(cursor.encode("ascii")))
+        return {"token": decoded["token"], "skip": int(decoded["skip"])}
+    except (binascii.Error, UnicodeError, ValueError, KeyError, TypeError):
+        raise ValueError(f"Invalid cursor: {cursor}")
+
+
+def list_documents(
+    store: VectorStore,
+    prefix: Optional[str] = None,
+    namespace: Optional[str] = None,
+    limit: int = DEFAULT_LIST_LIMIT,
+    cursor: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    List documents by grouping their chunk records, a page at a time.
+
+    Parameters:
+        store: The vector store.
+        prefix: Optional document ID prefix to filter by.
+        namespace: Optional namespace to list documents from.
+        limit: The number of documents to return per page.
+        cursor: Optional cursor returned with the previous page.
+
+    Returns:
+        Dict[str, Any]: The documents, each with its id, chunk count and
+        title if it has one, and the cursor of the next page, None on the last.
+    """
+    # The cursor is a record page plus how many of its records were already
+    # returned, since a document's chunks can span record pages
+    position = _decode_cursor(cursor) if cursor else {"token": None, "skip": 0}
+    pagination_token, skip = position["token"], position["skip"]
+
+    documents: Dict[str, Dict[str, Any]] = {}
+    next_cursor = None
+    while next_cursor is None:
+        page = store.list_records(
+            prefix=prefix, namespace=namespace, pagination_token=pagination_token
+        )
+        records = page.get("vectors", [])
+        for i, record in enumerate(records[skip:], skip):
+            doc_id = record_document_id(record["id"])
+            if doc_id not in documents:
+                if len(documents) == limit:
+                    next_cursor = _encode_cursor(pagination_token, i)
+                    break
+                documents[doc_id] = {
+                    "id": doc_id,
+                    "chunks": 0,
+                    "first_chunk": record["id"],
+                    "title": (record.get("metadata") or {}).get("title"),
+                }
+            documents[doc_id]["chunks"] += 1
+
+        skip = 0
+        pagination_token = page.get("pagination_token")
+        if not pagination_token:
+            break
+
+    # Pinecone lists IDs only, so read titles from each document's first chunk
+    missing = [doc["first_chunk"] for doc in documents.values() if not doc["title"]]
+    titles = fetch_metadata(store, missing, namespace=namespace) if missing else {}
+    for doc in documents.values():
+        first_chunk = doc.pop("first_chunk")
+        doc["title"] = doc["title"] or titles.get(first_chunk, {}).get("title")
+
+    return {"documents": list(documents.values()), "cursor": next_cursor}
+
+
+def delete_record_ids(
+    store: VectorStore, ids: List[str], namespace: Optional[str] = None
+) -> int:
+    """
+    Delete many records, in batches the delete API accepts.
+
+    Parameters:
+        store: The vector store.
+        ids: The record IDs.
+        namespace: Optional namespace to delete from.
+
+    Returns:
+        int: The number of IDs deleted.
+    """
+    for start in range(0, len(ids), DELETE_BATCH_SIZE):
+        batch = ids[start : start + DELETE_BATCH_SIZE]
+        store.delete_records(batch, namespace=namespace)
+    return len(ids)
+
+
+def create_vector_store(
+    config: ServerConfig, blob_store: Optional[BlobStore] = None
+) -> VectorStore:
+    """
+    Create the vector store for the configured backend.
+
+    Parameters:
+        config: The server configuration.
+        blob_store: Optional store for text too large for Pinecone metadata.
+
+    Returns:
+        VectorStore: The vector store instance.
+    """
+    embedder = create_embedder(config)
+    sparse_encoder = create_sparse_encoder(config)
+
+    if config.backend == "pinecone":
+        from .pinecone import PineconeClient
+
+        return PineconeClient(
+            config.pinecone,
+            embedder=embedder,
+            sparse_encoder=sparse_encoder,
+            blob_store=blob_store,
+        )
+    if config.backend == "memory":
+        from .memory_store import InMemoryVectorStore
+
+        return InMemoryVectorStore(
+            dimension=config.pinecone.dimension,
+            embedder=embedder,
+            sparse_encoder=sparse_encoder,
+        )
+    if config.backend == "sqlite":
+        from .sqlite_store import SQLiteVectorStore
+
+        return SQLiteVectorStore(
+            os.path.expanduser(config.database_path),
+            dimension=config.pinecone.dimension,
+            embedder=embedder,
+            sparse_encoder=sparse_encoder,
+        )
+    raise ValueError(
+        f"Unknown backend: {config.backend}. Expected one of: {', '.join(VECTOR_BACKENDS)}"
+    )
@@ -0,0 +1,115 @@
+import hashlib
+import logging
+import os
+import re
+import tempfile
+from typing import Any, Dict, Optional
+
+from .constants import DEFAULT_INLINE_TEXT_MAX_BYTES, DEFAULT_PREVIEW_CHARS
+
+logger = logging.getLogger(__name__)
+
+# References look like sha256:<hex digest of the content>
+REF_PATTERN = re.compile(r"sha256:([0-9a-f]{64})")
+
+
+class BlobStore:
+    """
+    A content-addressed store of chunk text and binary payloads too large
+    for vector metadata. Blobs are files named by the SHA-256 of their bytes,
+    so storing the same content twice costs nothing.
+    """
+
+    def __init__(
+        self,
+        path: str,
+        inline_max_bytes: int = DEFAULT_INLINE_TEXT_MAX_BYTES,
+        preview_chars: int = DEFAULT_PREVIEW_CHARS,
+    ):
+        self.path = path
+        # Text over this size is stored here, metadata keeps a preview
+        self.inline_max_bytes = inline_max_bytes
+        self.preview_chars = preview_chars
+
+    def _file(self, ref: str) -> str:
+        match = REF_PATTERN.fullmatch(ref)
+        if not match:
+            raise ValueError(f"Invalid blob reference: {ref}")
+        digest = match.group(1)
+        return os.path.join(self.path, digest[:2], digest)
+
+    def put(self, data: bytes) -> str:
+        """
+        Store a blob.
+
+        Parameters:
+            data: The bytes to store.
+
+        Returns:
+            str: The reference to read the blob back with.
+        """
+        ref = f"sha256:{hashlib.sha256(data).hexdigest()}"
+        file = self._file(ref)
+        if os.path.exists(file):
+            return ref
+
+        directory = os.path.dirname(file)
+        os.makedirs(directory, exist_ok=True)
+        # Write to a temporary file first so readers never see a partial blob
+        fd, temp = tempfile.mkstemp(dir=directory)
+        try:
+            with os.fdopen(fd, "wb") as f:
+                f.write(data)
+            os.replace(temp, file)
+        except Exception:
+            os.unlink(temp)
+            raise
+        return ref
+
+    def get(self, ref: str) -> bytes:
+        """
+        Read a blob.
+
+        Parameters:
+            ref: The reference returned by put.
+
+        Returns:
+            bytes: The stored bytes.
+        """
+        try:
+            with open(self._file(ref), "rb") as f:
+                return f.read()
+        except FileNotFoundError:
+            raise ValueError(f"Blob not found: {ref}")
+
+    def put_text(self, text: str) -> str:
+        return self.put(text.encode("utf-8"))
+
+    def get_text(self, ref: str) -> str:
+        return self.get(ref).decode("utf-8")
+
+
+def resolve_text(blob_store: Optional[BlobStore], metadata: Dict[str, Any]) -> str:
+    """
+    The full text of a record, read from the blob store when its metadata
+    only holds a preview.
+
+    Parameters:
+        blob_store: The blob store, None if not configured.
+        metadata: The record's metadata.
+
+    Returns:
+        str: The full text, or the preview if the blob cannot be read.
+    """
+    preview = metadata.get("text", "")
+    ref = metadata.get("text_ref")
+    if not ref:
+        return preview
+    if blob_store is None:
+        logger.warning(f"No blob store configured to resolve {ref}")
+        return preview
+    try:
+        return blob_store.get_text(ref)
+    except ValueError as e:
+        logger.warning(f"Falling back to the text preview: {e}")
+        return preview
@@ -0,0 +1,295 @@
+import argparse
+import hashlib
+import logging
+import os
+import sqlite3
+import time
+from array import array
+from typing import Any, Dict, List, Optional
+
+from .config import read_config_file
+from .constants import DEFAULT_CACHE_MAX_SIZE_MB, DEFAULT_CACHE_PATH
+from .embeddings import Embedder
+
+logger = logging.getLogger(__name__)
+
+SCHEMA = """
+CREATE TABLE IF NOT EXISTS embeddings (
+    model TEXT NOT NULL,
+    input_type TEXT NOT NULL,
+    text_hash TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    size INTEGER NOT NULL,
+    last_used REAL NOT NULL,
+    PRIMARY KEY (model, input_type, text_hash)
+);
+CREATE INDEX IF NOT EXISTS embeddings_last_used ON embeddings (last_used);
+"""
+
+# Evict down to this fraction of the limit so every insert doesn't trigger eviction
+EVICTION_TARGET = 0.9
+
+
+def text_hash(text: str) -> str:
+    return hashlib.sha256(text.encode("utf-8")).hexdigest()
+
+
+class EmbeddingCache:
+    """
+    A content-addressed on-disk cache of embeddings, keyed by
+    (model, input_type, text hash) and evicted least recently used first.
+    """
+
+    def __init__(self, path: str, max_size_mb: int):
+        self.path = path
+        self.max_bytes = max_size_mb * 1024 * 1024
+
+        directory = os.path.dirname(path)
+        if directory:
+            os.makedirs(directory, exist_ok=True)
+
+        self.conn = sqlite3.connect(path, check_same_thread=False)
+        self.conn.executescript(SCHEMA)
+
+    def get_many(
+        self, model: str, input_type: str, texts: List[str]
+    ) -> List[Optional[List[float]]]:
+        """
+        Look up cached embeddings.
+
+        Parameters:
+            model: The model that generated the embeddings.
+            input_type: "query" or "passage".
+            texts: The texts to look up.
+
+        Returns:
+            List[Optional[List[float]]]: The embeddings, None for misses.
+        """
+        results = []
+        hits = []
+        for text in texts:
+            key = text_hash(text)
+            row = self.conn.execute(
+                "SELECT embedding FROM embeddings "
+                "WHERE model = ? AND input_type = ? AND text_hash = ?",
+                (model, input_type, key),
+            ).fetchone()
+            if row is None:
+                results.append(None)
+                continue
+            values = array("f")
+            values.frombytes(row[0])
+            results.append(values.tolist())
+            hits.append((time.time(), model, input_type, key))
+
+        if hits:
+            with self.conn:
+                self.conn.executemany(
+                    "UPDATE embeddings SET last_used = ? "
+                    "WHERE model = ? AND input_type = ? AND text_hash = ?",
+                    hits,
+                )
+        return results
+
+    def put_many(
+        self,
+        model: str,
+        input_type: str,
+        texts: List[str],
+        embeddings: List[List[float]],
+    ):
+        """
+        Store embeddings and evict the least recently used past the size limit.
+
+        Parameters:
+            model: The model that generated the embeddings.
+            input_type: "query" or "passage".
+            texts: The texts that were embedded.
+            embeddings: The embeddings, in the same order as texts.
+        """
+        now = time.time()
+        rows = []
+        for text, embedding in zip(texts, embeddings):
+            blob = array("f", embedding).tobytes()
+            rows.append((model, input_type, text_hash(text), blob, len(blob), now))
+
+        with self.conn:
+            self.conn.executemany(
+                "INSERT OR REPLACE INTO embeddings "
+                "(model, input_type, text_hash, embedding, size, last_used) "
+                "VALUES (?, ?, ?, ?, ?, ?)",
+                rows,
+            )
+        self.evict()
+
+    def evict(self):
+        """
+        Delete least recently used entries until the cache fits its size limit.
+        """
+        total = self.stats()["size_bytes"]
+        if total <= self.max_bytes:
+            return
+
+        target = self.max_bytes * EVICTION_TARGET
+        rows = self.conn.execute(
+            "SELECT model, input_type, text_hash, size FROM embeddings "
+            "ORDER BY last_used ASC"
+        ).fetchall()
+
+        evicted = []
+        for model, input_type, key, size in rows:
+            if total <= target:
+                break
+            evicted.append((model, input_type, key))
+            total -= size
+
+        with self.conn:
+            self.conn.executemany(
+                "DELETE FROM embeddings "
+                "WHERE model = ? AND input_type = ? AND text_hash = ?",
+                evicted,
+            )
+        logger.info(f"Evicted {len(evicted)} cached embeddings")
+
+    def stats(self) -> Dict[str, Any]:
+        """
+        Summarize the cache contents.
+
+        Returns:
+            Dict[str, Any]: Entry count, size and per model counts.
+        """
+        entries, size = self.conn.execute(
+            "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM embeddings"
+        ).fetchone()
+        models = self.conn.execute(
+            "SELECT model, input_type, COUNT(*) FROM embeddings "
+            "GROUP BY model, input_type ORDER BY model, input_type"
+        ).fetchall()
+        return {
+            "path": self.path,
+            "entries": entries,
+            "size_bytes": size,
+            "max_bytes": self.max_bytes,
+            "models": [
+                {"model": model, "input_type": input_type, "entries": count}
+                for model, input_type, count in models
+            ],
+        }
+
+    def clear(self, model: Optional[str] = None) -> int:
+        """
+        Delete cached embeddings.
+
+        Parameters:
+            model: Optional model to clear, all models if not provided.
+
+        Returns:
+            int: The number of entries deleted.
+        """
+        with self.conn:
+            if model:
+                cursor = self.conn.execute(
+                    "DELETE FROM embeddings WHERE model = ?", (model,)
+                )
+            else:
+                cursor = self.conn.execute("DELETE FROM embeddings")
+        self.conn.execute("VACUUM")
+        return cursor.rowcount
+
+
+class CachedEmbedder:
+    """
+    Wraps an embedder so unchanged text is never embedded twice.
+    """
+
+    def __init__(self, embedder: Embedder, cache: EmbeddingCache, model: str):
+        self.embedder = embedder
+        self.cache = cache
+        self.model = model
+        self.dimension = embedder.dimension
+
+    def embed_query(self, text: str) -> List[float]:
+        """
+        Generate embeddings for a search query, from the cache when possible.
+
+        Parameters:
+            text: The query to generate embeddings for.
+
+        Returns:
+            List[float]: The embeddings for the query.
+        """
+        cached = self.cache.get_many(self.model, "query", [text])[0]
+        if cached is not None:
+            return cached
+        embedding = self.embedder.embed_query(text)
+        self.cache.put_many(self.model, "query", [text], [embedding])
+        return embedding
+
+    def embed_passages(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate embeddings for documents, only embedding cache misses.
+
+        Parameters:
+            texts: The passages to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The embeddings, in the same order as texts.
+        """
+        embeddings = self.cache.get_many(self.model, "passage", texts)
+        misses = [i for i, embedding in enumerate(embeddings) if embedding is None]
+        logger.info(f"Embedding cache hits: {len(texts) - len(misses)}/{len(texts)}")
+
+        if misses:
+            # Identical chunks within a document only need embedding once
+            unique = list(dict.fromkeys(texts[i] for i in misses))
+            fresh = dict(zip(unique, self.embedder.embed_passages(unique)))
+            self.cache.put_many(self.model, "passage", unique, list(fresh.values()))
+            for i in misses:
+                embeddings[i] = fresh[texts[i]]
+
+        return embeddings
+
+
+def main(argv: Optional[List[str]] = None):
+    """
+    Inspect or clear the embedding cache from the command line.
+    """
+    parser = argparse.ArgumentParser(description="Pinecone MCP embedding cache")
+    parser.add_argument(
+        "--path",
+        default=None,
+        help=f"Cache file. Will use environment variable MCP_PINECONE_CACHE_PATH, then cache.path from the config file if not provided, defaulting to {DEFAULT_CACHE_PATH}.",
+    )
+    parser.add_argument("--config", default=None, help="TOML config file.")
+    parser.add_argument("--profile", default=None, help="Profile in the config file.")
+    commands = parser.add_subparsers(dest="command", required=True)
+    commands.add_parser("stats", help="Show cache size and entries per model.")
+    clear = commands.add_parser("clear", help="Delete cached embeddings.")
+    clear.add_argument(
+        "--model", default=None, help="Only clear this model, as listed by stats."
+    )
+    args = parser.parse_args(argv)
+
+    settings = read_config_file(
+        args.config or os.getenv("MCP_PINECONE_CONFIG"),
+        args.profile or os.getenv("MCP_PINECONE_PROFILE"),
+    ).get("cache", {})
+    path = (
+        args.path
+        or os.getenv("MCP_PINECONE_CACHE_PATH")
+        or settings.get("path")
+        or DEFAULT_CACHE_PATH
+    )
+    max_size_mb = settings.get("max_size_mb", DEFAULT_CACHE_MAX_SIZE_MB)
+    cache = EmbeddingCache(os.path.expanduser(path), max_size_mb)
+
+    if args.command == "stats":
+        stats = cache.stats()
+        print(f"Path: {stats['path']}")
+        print(f"Entries: {stats['entries']}")
+        print(f"Size: {stats['size_bytes'] / (1024 * 1024):.1f} MB")
+        for model in stats["models"]:
+            print(f"  {model['model']} ({model['input_type']}): {model['entries']}")
+    elif args.command == "clear":
+        deleted = cache.clear(args.model)
+        print(f"Deleted {deleted} cached embeddings")
@@ -1,7 +1,66 @@
-from typing import List, Dict, Any, Optional
+import hashlib
+import inspect
+import json
+import logging
+import re
+from typing import Callable, List, Dict, Any, Optional, Protocol, Tuple
 from dataclasses import dataclass
 from langchain.text_splitter import MarkdownHeaderTextSplitter
 
+from .code import detect_language, segment_code, split_lines
+from .config import ServerConfig
+from .constants import CHUNKING_STRATEGIES, DEFAULT_CHUNK_HEADERS
+from .utils import estimate_tokens
+
+logger = logging.getLogger(__name__)
+
+# Boundaries tried in order when text is too long, each with the string
+# that puts the pieces back together
+SEPARATORS = [
+    (re.compile(r"\n\s*\n"), "\n\n"),  # paragraphs
+    (re.compile(r"\n"), "\n"),  # lines
+    (re.compile(r"(?<=[.!?])\s+"), " "),  # sentences
+    (re.compile(r"(?<=[。！？])"), ""),  # sentences of unspaced scripts
+    (re.compile(r"\s+"), " "),  # words
+]
+
+PARAGRAPH_PATTERN = SEPARATORS[0][0]
+
+# Joins the headers above a chunk into its header_path
+HEADER_PATH_SEPARATOR = " > "
+
+# Metadata that locates a chunk in its document, outermost first
+BREADCRUMB_KEYS = ("title", "header_path", "symbol")
+
+# Metadata that describes a chunk rather than its document
+CHUNK_METADATA_KEYS = (
+    "text",
+    "text_ref",
+    "text_size",
+    "content_hash",
+    "doc_id",
+    "chunk_number",
+    "total_chunks",
+    "overlap_chars",
+    "header_path",
+    "kind",
+    "symbol",
+    "start_line",
+    "end_line",
+)
+
+# Metadata that only records where a chunk sits, updated without re-embedding
+POSITION_METADATA_KEYS = ("chunk_number", "total_chunks")
+
+# Metadata keys of markdown headers, h1 to h6
+HEADER_KEY_PATTERN = re.compile(r"h([1-6])")
+
+# Markdown heading lines, as written back into merged sections
+HEADING_PATTERN = re.compile(r"(#{1,6}) (.*)")
+
+# Fenced code block delimiters, whose content is never a heading
+FENCE_PREFIXES = ("```", "~~~")
+
 
 @dataclass
 class Chunk:
@@ -12,17 +71,485 @@ class Chunk:
     id: str
     content: str
     metadata: Dict[str, Any]
+    # Breadcrumb such as "Spec > Billing > Refunds", embedded but not stored
+    context: str = ""
+
+    @property
+    def embedding_text(self) -> str:
+        """
+        The text to embed, the content preceded by its breadcrumb
+        """
+        return f"{self.context}\n\n{self.content}" if self.context else self.content
+
+    @property
+    def content_hash(self) -> str:
+        """
+        Hash of the chunk's embedded text and metadata, to skip unchanged
+        chunks on re-upsert. Its position in the document is left out, so
+        adding a chunk elsewhere does not change the others.
+        """
+        metadata = {
+            key: value
+            for key, value in self.metadata.items()
+            if key not in POSITION_METADATA_KEYS and key != "content_hash"
+        }
+        payload = json.dumps(
+            {"text": self.embedding_text, "metadata": metadata},
+            sort_keys=True,
+            default=str,
+        )
+        return hashlib.sha256(payload.encode("utf-8")).hexdigest()
+
+
+class Chunker(Protocol):
+    """
+    Splits a document into chunks for embedding
+    """
+
+    def chunk_document(
+        self, doc_id: str, content: str, metadata: Optional[Dict[str, Any]] = None
+    ) -> List[Chunk]: ...
+
+
+def tail(text: str, tokens: int) -> str:
+    """
+    The end of a text, cut at a word boundary
+
+    Parameters:
+        text: The text to take the end of
+        tokens: Maximum estimated tokens to keep
+
+    Returns:
+        The last words of the text
+    """
+    words = []
+    count = 0
+    for word in reversed(text.split()):
+        count += estimate_tokens(word)
+        if count > tokens:
+            break
+        words.append(word)
+    return " ".join(reversed(words))
+
+
+class RecursiveSplitter:
+    """
+    Splits text into pieces within a token limit, preferring paragraph,
+    then line, then sentence, then word boundaries. Consecutive pieces
+    repeat overlap_tokens of text so nothing loses its context at a cut.
+    """
+
+    def __init__(
+        self,
+        max_tokens: int,
+        overlap_tokens: int = 0,
+        separators: Optional[List[Tuple[re.Pattern, str]]] = None,
+    ):
+        self.max_tokens = max_tokens
+        self.overlap_tokens = overlap_tokens
+        self.separators = separators or SEPARATORS
+
+    def split(
+        self, text: str, max_tokens: Optional[int] = None
+    ) -> List[Tuple[str, int]]:
+        """
+        Split text into pieces of at most max_tokens estimated tokens
+
+        Parameters:
+            text: The text to split
+            max_tokens: Optional smaller limit for this text, such as
+                what is left next to a breadcrumb
+
+        Returns:
+            The pieces in order, each with how many of its leading characters
+            repeat the piece before it. Just the text if it already fits
+        """
+        max_tokens = max_tokens or self.max_tokens
+        if estimate_tokens(text) <= max_tokens:
+            return [(text, 0)]
+
+        # Leave room for the overlap carried into each piece
+        overlap_tokens = min(self.overlap_tokens, max_tokens // 2)
+        pieces = self._split(text, max_tokens - overlap_tokens, 0)
+        if not overlap_tokens:
+            return [(piece, 0) for piece in pieces]
+
+        overlapped = [(pieces[0], 0)]
+        for previous, piece in zip(pieces, pieces[1:]):
+            overlap = tail(previous, overlap_tokens)
+            if overlap:
+                overlapped.append((f"{overlap} {piece}", len(overlap) + 1))
+            else:
+                overlapped.append((piece, 0))
+        return overlapped
+
+    def _split(self, text: str, budget: int, level: int) -> List[str]:
+        if estimate_tokens(text) <= budget:
+            return [text]
+
+        if level == len(self.separators):
+            # A run without whitespace, such as a long URL, is cut anywhere
+            size = max(1, len(text) * (budget - 1) // estimate_tokens(text))
+            return [text[i : i + size] for i in range(0, len(text), size)]
+
+        pattern, joiner = self.separators[level]
+        pieces = []
+        for part in pattern.split(text):
+            if part.strip():
+                pieces.extend(self._split(part, budget, level + 1))
+        return self._pack(pieces, budget, joiner)
+
+    def _pack(self, pieces: List[str], budget: int, joiner: str) -> List[str]:
+        # Greedily join neighbouring pieces back up to the budget
+        packed = []
+        current: List[str] = []
+        current_tokens = 0
+        for piece in pieces:
+            tokens = estimate_tokens(piece)
+            if current and current_tokens + tokens > budget:
+                packed.append(joiner.join(current))
+                current, current_tokens = [], 0
+            current.append(piece)
+            current_tokens += tokens
+        if current:
+            packed.append(joiner.join(current))
+        return packed
+
+
+def overlap_metadata(overlap_chars: int) -> Dict[str, int]:
+    """
+    Metadata recording the overlap a piece starts with, so reassembling
+    the document removes exactly that much
+
+    Parameters:
+        overlap_chars: Leading characters repeating the previous piece
+
+    Returns:
+        The overlap_chars field, empty without overlap
+    """
+    return {"overlap_chars": overlap_chars} if overlap_chars else {}
+
+
+def flatten_headers(headers: Dict[str, str]) -> Dict[str, str]:
+    """
+    Turn the header hierarchy of a section into scalar metadata fields,
+    since Pinecone metadata cannot nest
+
+    Parameters:
+        headers: Header text keyed by level, e.g. {"h1": "Guide", "h2": "Setup"},
+            other section fields such as overlap_chars are kept as they are
+
+    Returns:
+        The headers plus a header_path such as "Guide > Setup"
+    """
+    levels = sorted(
+        (key for key in headers if HEADER_KEY_PATTERN.fullmatch(key)),
+        key=lambda key: int(key[1:]),
+    )
+    if not levels:
+        return dict(headers)
+    return {
+        **{level: headers[level] for level in levels},
+        **{key: value for key, value in headers.items() if key not in levels},
+        "header_path": HEADER_PATH_SEPARATOR.join(headers[level] for level in levels),
+    }
+
+
+def breadcrumb(metadata: Dict[str, Any], max_tokens: Optional[int] = None) -> str:
+    """
+    Describe where a chunk sits, from its document title down to its
+    headers or symbol
+
+    Parameters:
+        metadata: The chunk's metadata
+        max_tokens: Optional limit, longer breadcrumbs keep their innermost end
+
+    Returns:
+        A breadcrumb such as "Spec > Billing > Refunds", empty if nothing is known
+    """
+    parts = [str(metadata[key]) for key in BREADCRUMB_KEYS if metadata.get(key)]
+    text = HEADER_PATH_SEPARATOR.join(parts)
+    if max_tokens is not None and estimate_tokens(text) > max_tokens:
+        text = tail(text, max_tokens).lstrip("> ")
+    return text
+
+
+def content_budget(
+    max_tokens: int, metadata: Dict[str, Any], breadcrumbs: bool
+) -> int:
+    """
+    Estimated tokens left for a chunk's text once its breadcrumb is prepended
+
+    Parameters:
+        max_tokens: The chunk size limit
+        metadata: What is known of the chunk's metadata before it is split
+        breadcrumbs: Whether breadcrumbs are prepended at all
+
+    Returns:
+        The limit for the chunk's text, max_tokens without a breadcrumb
+    """
+    if not breadcrumbs:
+        return max_tokens
+    # Breadcrumbs are capped at half the chunk, see build_chunks
+    context = breadcrumb(metadata, max_tokens // 2)
+    return max(1, max_tokens - estimate_tokens(context))
+
+
+def build_chunks(
+    doc_id: str,
+    sections: List[Tuple[str, Dict[str, Any]]],
+    metadata: Optional[Dict[str, Any]] = None,
+    breadcrumbs: bool = False,
+    max_tokens: Optional[int] = None,
+) -> List[Chunk]:
+    """
+    Turn split sections into numbered chunks
+
+    Parameters:
+        doc_id: Unique document identifier
+        sections: Text and metadata of each section, in document order
+        metadata: Optional metadata to include with chunks
+        breadcrumbs: Whether to give each chunk a breadcrumb to embed with it
+        max_tokens: Optional chunk size limit, breadcrumbs take at most half
+
+    Returns:
+        List of Chunk objects with IDs and metadata
+    """
+    chunks = []
+
+    # Process each section into a chunk
+    for i, (text, section_metadata) in enumerate(sections):
+        # Create chunk metadata combining:
+        # 1. Additional passed metadata
+        # 2. What the chunker knows about the section, such as its headers
+        # 3. Document position, which reassembling the document relies on
+        chunk_metadata = {
+            **(metadata or {}),
+            **section_metadata,
+            "doc_id": doc_id,
+            "chunk_number": i + 1,
+            "total_chunks": len(sections),
+        }
+
+        chunk = Chunk(
+            id=f"{doc_id}#chunk{i+1}",
+            content=text,
+            metadata=chunk_metadata,
+        )
+        if breadcrumbs:
+            chunk.context = breadcrumb(
+                chunk_metadata, max_tokens // 2 if max_tokens else None
+            )
+        chunks.append(chunk)
+
+    return chunks
+
+
+def _headers(metadata: Dict[str, Any]) -> Dict[int, str]:
+    headers = {}
+    for key, value in metadata.items():
+        match = HEADER_KEY_PATTERN.fullmatch(key)
+        if match:
+            headers[int(match.group(1))] = value
+    return headers
+
+
+def _restore_headings(
+    text: str, headers: Dict[str, Any], current: Dict[int, str]
+) -> str:
+    # A merged section only keeps the headers it shares with its neighbour,
+    # so write the others back as heading lines to keep them searchable
+    lines = []
+    for level, value in sorted(_headers(headers).items()):
+        if lines or current.get(level) != value:
+            lines.append(f"{'#' * level} {value}")
+    return "\n".join(lines + [text])
+
+
+def _drop_restated_headings(headers: Dict[int, str], text: str) -> str:
+    # Merged sections restate their headings so each chunk stands on its own,
+    # skip the ones the document is already under
+    lines = text.split("\n")
+    while len(lines) > 1:
+        match = HEADING_PATTERN.fullmatch(lines[0])
+        if not match or headers.get(len(match.group(1))) != match.group(2):
+            break
+        lines.pop(0)
+    return "\n".join(lines)
+
+
+def _trailing_headers(headers: Dict[int, str], text: str) -> Dict[int, str]:
+    # The headers in effect at the end of a chunk, after any heading lines
+    # restored into its text by merge_small
+    current = dict(headers)
+    in_fence = False
+    for line in text.split("\n"):
+        if line.lstrip().startswith(FENCE_PREFIXES):
+            in_fence = not in_fence
+            continue
+        match = HEADING_PATTERN.fullmatch(line)
+        if match and not in_fence:
+            level = len(match.group(1))
+            current = {key: value for key, value in current.items() if key < level}
+            current[level] = match.group(2)
+    return current
+
+
+def join_chunks(chunks: List[Tuple[str, Dict[str, Any]]]) -> str:
+    """
+    Put a document back together from its chunks, restoring the markdown
+    headers the splitter removed and dropping the overlap between pieces
+
+    Parameters:
+        chunks: Full text and metadata of each chunk, in chunk_number order
+
+    Returns:
+        The document text
+    """
+    document = ""
+    previous_metadata: Dict[str, Any] = {}
+    previous_headers: Dict[int, str] = {}
+    for index, (text, metadata) in enumerate(chunks):
+        headers = _headers(metadata)
+
+        if not index:
+            separator = ""
+        elif "start_line" in metadata and "end_line" in previous_metadata:
+            # Code chunks are line ranges, restore the blank lines between them
+            gap = metadata["start_line"] - previous_metadata["end_line"]
+            separator = "\n" * max(gap, 1)
+        else:
+            separator = "\n\n"
+            # Split pieces start with the end of the piece before them
+            text = text[metadata.get("overlap_chars", 0) :]
+
+        # Repeat every header from the first level that changed
+        lines = []
+        for level in sorted(headers):
+            if lines or headers[level] != previous_headers.get(level):
+                lines.append(f"{'#' * level} {headers[level]}")
+
+        current = headers if lines else previous_headers
+        if "start_line" not in metadata:
+            text = _drop_restated_headings(current, text)
+            current = _trailing_headers(current, text)
+
+        document += separator + "\n".join(lines + [text])
+        previous_metadata, previous_headers = metadata, current
+    return document
+
+
+def document_metadata(chunks_metadata: List[Dict[str, Any]]) -> Dict[str, Any]:
+    """
+    The metadata a document was upserted with, the fields all of its
+    chunks share besides those describing each chunk
+
+    Parameters:
+        chunks_metadata: The metadata of each chunk
+
+    Returns:
+        The shared metadata
+    """
+    if not chunks_metadata:
+        return {}
+    shared = {
+        key: value
+        for key, value in chunks_metadata[0].items()
+        if key not in CHUNK_METADATA_KEYS and not HEADER_KEY_PATTERN.fullmatch(key)
+    }
+    for metadata in chunks_metadata[1:]:
+        shared = {
+            key: value for key, value in shared.items() if metadata.get(key) == value
+        }
+    return shared
+
+
+def merge_small(
+    sections: List[Tuple[str, Dict[str, Any]]],
+    min_tokens: int,
+    max_tokens: int,
+    budget: Optional[Callable[[Dict[str, Any]], int]] = None,
+) -> List[Tuple[str, Dict[str, Any]]]:
+    """
+    Merge sections below min_tokens into their previous neighbour when the
+    result still fits in max_tokens
+
+    Parameters:
+        sections: Text and metadata of each section, its headers and any
+            overlap_chars, in document order
+        min_tokens: Sections with fewer estimated tokens are merged
+        max_tokens: Merged sections may not exceed this many estimated tokens
+        budget: Optional limit for a merged section given its shared headers,
+            replacing max_tokens
+
+    Returns:
+        The merged sections, keeping only the headers they share in their
+        metadata and the others as heading lines in their text
+    """
+    merged: List[Tuple[str, Dict[str, Any]]] = []
+    for text, metadata in sections:
+        if merged:
+            previous_text, previous_metadata = merged[-1]
+            small = (
+                estimate_tokens(previous_text) < min_tokens
+                or estimate_tokens(text) < min_tokens
+            )
+            # Headers down to the first level where the sections differ
+            shared: Dict[str, Any] = {}
+            for level, value in sorted(_headers(previous_metadata).items()):
+                if metadata.get(f"h{level}") != value:
+                    break
+                shared[f"h{level}"] = value
+            restored = _restore_headings(
+                previous_text, previous_metadata, _headers(shared)
+            )
+            current = _trailing_headers(_headers(previous_metadata), restored)
+            # A piece's overlap repeats the end of the section it now follows
+            continued = text[metadata.get("overlap_chars", 0) :]
+            merged_text = (
+                f"{restored}\n\n{_restore_headings(continued, metadata, current)}"
+            )
+            limit = budget(shared) if budget else max_tokens
+            if small and estimate_tokens(merged_text) <= limit:
+                # Headings restored in front of an overlap restate the
+                # section it continues, so they are dropped along with it
+                overlap_chars = previous_metadata.get("overlap_chars", 0)
+                if overlap_chars:
+                    overlap_chars += len(restored) - len(previous_text)
+                merged_metadata = {**shared, **overlap_metadata(overlap_chars)}
+                merged[-1] = (merged_text, merged_metadata)
+                continue
+        merged.append((text, metadata))
+    return merged
 
 
 class MarkdownChunker:
     """
     Chunks documents based on markdown structure
-    Currently just on h1, h2, h3 headers
+    Defaults to h1, h2, h3 headers
+    With max_tokens set, oversized sections are split further and
+    sections under min_tokens are merged into their neighbours
+    With breadcrumbs set, each chunk is embedded after its title and headers
     """
 
-    def __init__(self):
+    def __init__(
+        self,
+        headers: Optional[List[str]] = None,
+        max_tokens: Optional[int] = None,
+        min_tokens: int = 0,
+        overlap_tokens: int = 0,
+        breadcrumbs: bool = False,
+    ):
+        headers = headers or DEFAULT_CHUNK_HEADERS
+        # "##" is stored as metadata key "h2" and so on
         self.splitter = MarkdownHeaderTextSplitter(
-            headers_to_split_on=[("#", "h1"), ("##", "h2"), ("###", "h3")]
+            headers_to_split_on=[(header, f"h{len(header)}") for header in headers]
+        )
+        self.max_tokens = max_tokens
+        self.min_tokens = min_tokens
+        self.breadcrumbs = breadcrumbs
+        self.recursive_splitter = (
+            RecursiveSplitter(max_tokens, overlap_tokens) if max_tokens else None
         )
 
     def chunk_document(
@@ -41,31 +568,357 @@ class MarkdownChunker:
         """
         try:
             # Split based on markdown headers
-            splits = self.splitter.split_text(content)
-            chunks = []
-
-            # Process each split into a chunk
-            for i, split in enumerate(splits):
-                # Create chunk metadata combining:
-                # 1. Header hierarchy from the split
-                # 2. Document metadata
-                # 3. Additional passed metadata
-                chunk_metadata = {
-                    "doc_id": doc_id,
-                    "chunk_number": i + 1,
-                    "total_chunks": len(splits),
-                    "headers": split.metadata,
-                    **(metadata or {}),
-                }
+            sections = [
+                (split.page_content, split.metadata)
+                for split in self.splitter.split_text(content)
+            ]
 
-                chunk = Chunk(
-                    id=f"{doc_id}#chunk{i+1}",
-                    content=split.page_content,
-                    metadata=chunk_metadata,
+            # Then keep every section within the embedding model's limit,
+            # leaving room for its breadcrumb
+            if self.recursive_splitter:
+
+                def budget(headers):
+                    return content_budget(
+                        self.max_tokens,
+                        {**(metadata or {}), **flatten_headers(headers)},
+                        self.breadcrumbs,
+                    )
+
+                sections = [
+                    (piece, {**headers, **overlap_metadata(overlap)})
+                    for text, headers in sections
+                    for piece, overlap in self.recursive_splitter.split(
+                        text, budget(headers)
+                    )
+                ]
+                sections = merge_small(
+                    sections, self.min_tokens, self.max_tokens, budget
                 )
-                chunks.append(chunk)
 
-            return chunks
+            sections = [(text, flatten_headers(headers)) for text, headers in sections]
+            return build_chunks(
+                doc_id, sections, metadata, self.breadcrumbs, self.max_tokens
+            )
 
         except Exception as e:
             raise RuntimeError(f"Error chunking document: {str(e)}")
+
+
+class FixedSizeChunker:
+    """
+    Chunks documents into windows of max_tokens, ignoring their structure
+    """
+
+    def __init__(
+        self, max_tokens: int, overlap_tokens: int = 0, breadcrumbs: bool = False
+    ):
+        self.max_tokens = max_tokens
+        self.breadcrumbs = breadcrumbs
+        # Only split between words
+        self.splitter = RecursiveSplitter(max_tokens, overlap_tokens, SEPARATORS[-1:])
+
+    def chunk_document(
+        self, doc_id: str, content: str, metadata: Optional[Dict[str, Any]] = None
+    ) -> List[Chunk]:
+        """
+        Split document into fixed size chunks
+
+        Parameters:
+            doc_id: Unique document identifier
+            content: Document text content
+            metadata: Optional metadata to include with chunks
+
+        Returns:
+            List of Chunk objects with IDs and metadata
+        """
+        if not content.strip():
+            return []
+        budget = content_budget(self.max_tokens, metadata or {}, self.breadcrumbs)
+        sections = [
+            (piece, overlap_metadata(overlap))
+            for piece, overlap in self.splitter.split(content, budget)
+        ]
+        return build_chunks(
+            doc_id, sections, metadata, self.breadcrumbs, self.max_tokens
+        )
+
+
+class SentenceChunker:
+    """
+    Chunks documents into runs of whole sentences up to max_tokens
+    """
+
+    def __init__(
+        self, max_tokens: int, overlap_tokens: int = 0, breadcrumbs: bool = False
+    ):
+        self.max_tokens = max_tokens
+        self.breadcrumbs = breadcrumbs
+        # Split between sentences, or words for sentences over the limit
+        self.splitter = RecursiveSplitter(max_tokens, overlap_tokens, SEPARATORS[2:])
+
+    def chunk_document(
+        self, doc_id: str, content: str, metadata: Optional[Dict[str, Any]] = None
+    ) -> List[Chunk]:
+        """
+        Split document into chunks of whole sentences
+
+        Parameters:
+            doc_id: Unique document identifier
+            content: Document text content
+            metadata: Optional metadata to include with chunks
+
+        Returns:
+            List of Chunk objects with IDs and metadata
+        """
+        if not content.strip():
+            return []
+        budget = content_budget(self.max_tokens, metadata or {}, self.breadcrumbs)
+        sections = [
+            (piece, overlap_metadata(overlap))
+            for piece, overlap in self.splitter.split(content, budget)
+        ]
+        return build_chunks(
+            doc_id, sections, metadata, self.breadcrumbs, self.max_tokens
+        )
+
+
+class ParagraphChunker:
+    """
+    Chunks documents one paragraph per chunk, splitting paragraphs over
+    max_tokens and merging those under min_tokens into their neighbours
+    """
+
+    def __init__(
+        self,
+        max_tokens: int,
+        min_tokens: int = 0,
+        overlap_tokens: int = 0,
+        breadcrumbs: bool = False,
+    ):
+        self.max_tokens = max_tokens
+        self.min_tokens = min_tokens
+        self.breadcrumbs = breadcrumbs
+        self.splitter = RecursiveSplitter(max_tokens, overlap_tokens)
+
+    def chunk_document(
+        self, doc_id: str, content: str, metadata: Optional[Dict[str, Any]] = None
+    ) -> List[Chunk]:
+        """
+        Split document into chunks on blank lines
+
+        Parameters:
+            doc_id: Unique document identifier
+            content: Document text content
+            metadata: Optional metadata to include with chunks
+
+        Returns:
+            List of Chunk objects with IDs and metadata
+        """
+        budget = content_budget(self.max_tokens, metadata or {}, self.breadcrumbs)
+        sections = [
+            (piece, overlap_metadata(overlap))
+            for paragraph in PARAGRAPH_PATTERN.split(content)
+            if paragraph.strip()
+            for piece, overlap in self.splitter.split(paragraph.strip(), budget)
+        ]
+        sections = merge_small(sections, self.min_tokens, budget)
+        return build_chunks(
+            doc_id, sections, metadata, self.breadcrumbs, self.max_tokens
+        )
+
+
+class WholeDocumentChunker:
+    """
+    Keeps each document as a single chunk
+    Text past the embedding model's limit is truncated when embedded
+    """
+
+    def __init__(self, max_tokens: Optional[int] = None, breadcrumbs: bool = False):
+        self.max_tokens = max_tokens
+        self.breadcrumbs = breadcrumbs
+
+    def chunk_document(
+        self, doc_id: str, content: str, metadata: Optional[Dict[str, Any]] = None
+    ) -> List[Chunk]:
+        """
+        Wrap the whole document in one chunk
+
+        Parameters:
+            doc_id: Unique document identifier
+            content: Document text content
+            metadata: Optional metadata to include with chunks
+
+        Returns:
+            A list with the single Chunk, empty for a blank document
+        """
+        if not content.strip():
+            return []
+        chunks = build_chunks(
+            doc_id, [(content, {})], metadata, self.breadcrumbs, self.max_tokens
+        )
+        tokens = estimate_tokens(chunks[0].embedding_text)
+        if self.max_tokens and tokens > self.max_tokens:
+            logger.warning(
+                f"Document {doc_id} exceeds {self.max_tokens} tokens, "
+                "the embedding will only cover its beginning"
+            )
+        return chunks
+
+
+class CodeChunker:
+    """
+    Chunks source code by top-level functions and classes, splitting
+    Python classes too large for one chunk into their methods
+    Each chunk records its language, kind, symbol and line range
+    """
+
+    def __init__(
+        self,
+        language: Optional[str] = None,
+        max_tokens: int = 512,
+        breadcrumbs: bool = False,
+    ):
+        self.language = language
+        self.max_tokens = max_tokens
+        self.breadcrumbs = breadcrumbs
+
+    def chunk_document(
+        self, doc_id: str, content: str, metadata: Optional[Dict[str, Any]] = None
+    ) -> List[Chunk]:
+        """
+        Split source code into chunks of definitions
+
+        Parameters:
+            doc_id: Unique document identifier, a file path helps detect the language
+            content: Document text content
+            metadata: Optional metadata to include with chunks, a language
+                or path key helps detect the language
+
+        Returns:
+            List of Chunk objects with IDs and metadata
+        """
+        metadata = metadata or {}
+        language = (
+            self.language
+            or metadata.get("language")
+            or detect_language(metadata.get("path"))
+            or detect_language(doc_id)
+            or "text"
+        )
+
+        lines = split_lines(content)
+        sections = []
+        for segment in segment_code(content, language, self.max_tokens):
+            segment_metadata = {**metadata, "symbol": segment.symbol}
+            budget = content_budget(
+                self.max_tokens, segment_metadata, self.breadcrumbs
+            )
+            for start, end in self._split_lines(
+                lines, segment.start_line, segment.end_line, budget
+            ):
+                section_metadata = {
+                    "language": language,
+                    "kind": segment.kind,
+                    "start_line": start,
+                    "end_line": end,
+                }
+                if segment.symbol:
+                    section_metadata["symbol"] = segment.symbol
+                sections.append(("\n".join(lines[start - 1 : end]), section_metadata))
+
+        return build_chunks(
+            doc_id, sections, metadata, self.breadcrumbs, self.max_tokens
+        )
+
+    def _split_lines(self, lines: List[str], start: int, end: int, budget: int):
+        # Pack whole lines up to the budget, keeping exact line ranges
+        piece_start, tokens = start, 0
+        for number in range(start, end + 1):
+            line_tokens = estimate_tokens(lines[number - 1])
+            if number > piece_start and tokens + line_tokens > budget:
+                yield piece_start, number - 1
+                piece_start, tokens = number, 0
+            tokens += line_tokens
+        yield piece_start, end
+
+
+# Chunking strategies by name, see CHUNKING_STRATEGIES
+CHUNKERS = {
+    "markdown": MarkdownChunker,
+    "fixed": FixedSizeChunker,
+    "sentence": SentenceChunker,
+    "paragraph": ParagraphChunker,
+    "none": WholeDocumentChunker,
+    "code": CodeChunker,
+}
+
+
+def create_chunker(
+    config: ServerConfig,
+    namespace: Optional[str] = None,
+    chunking: Optional[Any] = None,
+) -> Chunker:
+    """
+    Create the chunker for an upsert. Settings are layered, later ones win:
+    the chunking config, the namespace's entry in chunking.namespaces and
+    the tool call's chunking argument. Only parameters of the tool call must
+    all apply to the chosen strategy, the others apply where they do.
+
+    Parameters:
+        config: The server configuration.
+        namespace: The namespace being upserted into.
+        chunking: Optional strategy name or dict of strategy and parameters.
+
+    Returns:
+        Chunker: The chunker instance.
+    """
+    if isinstance(chunking, str):
+        chunking = {"strategy": chunking}
+    elif chunking is not None and not isinstance(chunking, dict):
+        raise ValueError(f"chunking must be a strategy name or an object: {chunking}")
+
+    defaults = {
+        "headers": config.chunking.headers,
+        "max_tokens": config.chunk_max_tokens,
+        "min_tokens": config.chunking.min_tokens,
+        "overlap_tokens": config.chunking.overlap_tokens,
+        "breadcrumbs": config.chunking.breadcrumbs,
+    }
+    namespace_settings = dict(config.chunking.namespaces.get(namespace or "", {}))
+    settings = dict(chunking or {})
+    namespace_strategy = namespace_settings.pop("strategy", config.chunking.strategy)
+    strategy = settings.pop("strategy", namespace_strategy)
+    defaults.update(namespace_settings)
+
+    if strategy not in CHUNKERS:
+        raise ValueError(
+            f"Unknown chunking strategy: {strategy}. Expected one of: {', '.join(CHUNKING_STRATEGIES)}"
+        )
+
+    # Explicit settings must apply to the strategy, the namespace's settings
+    # are defaults too, since the call may pick a different strategy
+    accepted = inspect.signature(CHUNKERS[strategy]).parameters
+    unknown = set(settings) - set(accepted)
+    if unknown:
+        raise ValueError(
+            f"Unknown parameters for {strategy} chunking: {', '.join(sorted(unknown))}"
+        )
+    params = {
+        **{key: value for key, value in defaults.items() if key in accepted},
+        **settings,
+    }
+
+    max_tokens = params.get("max_tokens")
+    # A smaller max_tokens shrinks the default overlap along with it
+    configured = {**namespace_settings, **settings}
+    if max_tokens and "overlap_tokens" in params and "overlap_tokens" not in configured:
+        params["overlap_tokens"] = min(params["overlap_tokens"], max_tokens // 2)
+    overlap_tokens = params.get("overlap_tokens", 0)
+    if max_tokens is not None and max_tokens < 1:
+        raise ValueError(f"max_tokens must be positive, got: {max_tokens}")
+    if max_tokens and not 0 <= overlap_tokens <= max_tokens // 2:
+        raise ValueError(
+            f"overlap_tokens must be between 0 and half of max_tokens, got: {overlap_tokens}"
+        )
+
+    return CHUNKERS[strategy](**params)
@@ -0,0 +1,237 @@
+import ast
+import logging
+import os
+import re
+from dataclasses import dataclass
+from typing import List, Optional, Tuple
+
+from .utils import estimate_tokens
+
+logger = logging.getLogger(__name__)
+
+# File extensions of the languages the code chunker understands
+LANGUAGE_EXTENSIONS = {
+    ".py": "python",
+    ".pyi": "python",
+    ".js": "javascript",
+    ".jsx": "javascript",
+    ".mjs": "javascript",
+    ".cjs": "javascript",
+    ".ts": "typescript",
+    ".tsx": "typescript",
+    ".go": "go",
+    ".rs": "rust",
+    ".java": "java",
+}
+
+_JS_PATTERNS = [
+    (r"(export\s+)?(default\s+)?(async\s+)?function\s*\*?\s*(?P<name>\w+)", "function"),
+    (r"(export\s+)?(default\s+)?(abstract\s+)?class\s+(?P<name>\w+)", "class"),
+    (
+        r"(export\s+)?(const|let|var)\s+(?P<name>\w+)\s*(:[^=]+)?=\s*(async\s+)?"
+        r"(function\b|\([^)]*\)\s*(:[^=]+)?=>|\w+\s*=>)",
+        "function",
+    ),
+]
+
+# Top-level definitions of languages without a parser, matched at column 0
+DEFINITION_PATTERNS = {
+    "javascript": _JS_PATTERNS,
+    "typescript": _JS_PATTERNS
+    + [
+        (r"(export\s+)?(declare\s+)?interface\s+(?P<name>\w+)", "interface"),
+        (r"(export\s+)?(declare\s+)?type\s+(?P<name>\w+)", "type"),
+        (r"(export\s+)?(declare\s+)?(const\s+)?enum\s+(?P<name>\w+)", "enum"),
+    ],
+    "go": [
+        (r"func\s+\((?P<receiver>[^)]*)\)\s*(?P<name>\w+)", "method"),
+        (r"func\s+(?P<name>\w+)", "function"),
+        (r"type\s+(?P<name>\w+)\s+(struct|interface)\b", "type"),
+    ],
+    "rust": [
+        (
+            r"(pub(\([^)]*\))?\s+)?(const\s+)?(async\s+)?(unsafe\s+)?"
+            r"fn\s+(?P<name>\w+)",
+            "function",
+        ),
+        (r"(pub(\([^)]*\))?\s+)?(struct|enum|union)\s+(?P<name>\w+)", "type"),
+        (r"(pub(\([^)]*\))?\s+)?trait\s+(?P<name>\w+)", "trait"),
+        (r"impl(<[^>]*>)?\s+(?P<name>[\w:<>, ]+?)\s*(where\b.*)?\{?$", "impl"),
+        (r"(pub(\([^)]*\))?\s+)?mod\s+(?P<name>\w+)\s*\{", "module"),
+    ],
+    "java": [
+        (
+            r"(public\s+|protected\s+|private\s+)?(abstract\s+|final\s+|static\s+)*"
+            r"(class|interface|enum|record)\s+(?P<name>\w+)",
+            "class",
+        ),
+    ],
+}
+
+# Line breaks as Python counts them, unlike str.splitlines which also breaks
+# on form feeds and so would shift line numbers
+LINE_BREAK_PATTERN = re.compile(r"\r\n|\r|\n")
+
+# Comment, decorator and attribute lines directly above a definition belong to it
+LEADING_PATTERN = re.compile(r"\s*(#|//|/\*|\*|@)")
+
+
+@dataclass
+class Segment:
+    """
+    A run of source lines forming one definition, or module level code
+    """
+
+    kind: str
+    symbol: Optional[str]
+    # 1-based and inclusive
+    start_line: int
+    end_line: int
+
+
+def detect_language(path: Optional[str]) -> Optional[str]:
+    """
+    Guess a file's language from its extension
+
+    Parameters:
+        path: The file path or document ID
+
+    Returns:
+        The language, None if unknown
+    """
+    if not path:
+        return None
+    return LANGUAGE_EXTENSIONS.get(os.path.splitext(path)[1].lower())
+
+
+def split_lines(source: str) -> List[str]:
+    return LINE_BREAK_PATTERN.split(source)
+
+
+def _leading_start(lines: List[str], start: int, floor: int) -> int:
+    # Walk up over comments and decorators, stopping at blank lines
+    while start - 1 > floor and LEADING_PATTERN.match(lines[start - 2]):
+        start -= 1
+    return start
+
+
+def _trim(lines: List[str], segment: Segment) -> Optional[Segment]:
+    # Drop blank lines at either end, and the segment if nothing is left
+    start, end = segment.start_line, segment.end_line
+    while start <= end and not lines[start - 1].strip():
+        start += 1
+    while end >= start and not lines[end - 1].strip():
+        end -= 1
+    if start > end:
+        return None
+    return Segment(segment.kind, segment.symbol, start, end)
+
+
+def _tokens(lines: List[str], start: int, end: int) -> int:
+    return estimate_tokens("\n".join(lines[start - 1 : end]))
+
+
+def _python_segments(lines: List[str], source: str, max_tokens: int) -> List[Segment]:
+    tree = ast.parse(source)
+    functions = (ast.FunctionDef, ast.AsyncFunctionDef)
+
+    def start_of(node, floor):
+        decorators = [d.lineno for d in node.decorator_list]
+        return _leading_start(lines, min(decorators + [node.lineno]), floor)
+
+    segments = []
+    cursor = 1
+    for node in tree.body:
+        if not isinstance(node, functions + (ast.ClassDef,)):
+            continue
+        start, end = start_of(node, cursor - 1), node.end_lineno
+        if cursor < start:
+            segments.append(Segment("module", None, cursor, start - 1))
+        cursor = end + 1
+
+        if not isinstance(node, ast.ClassDef):
+            segments.append(Segment("function", node.name, start, end))
+            continue
+
+        # Classes that fit stay whole, larger ones are split into methods
+        methods = [n for n in node.body if isinstance(n, functions)]
+        if not methods or _tokens(lines, start, end) <= max_tokens:
+            segments.append(Segment("class", node.name, start, end))
+            continue
+
+        method_starts = [start_of(method, start) for method in methods]
+        segments.append(Segment("class", node.name, start, method_starts[0] - 1))
+        bounds = method_starts[1:] + [end + 1]
+        for method, method_start, next_start in zip(methods, method_starts, bounds):
+            symbol = f"{node.name}.{method.name}"
+            segments.append(Segment("method", symbol, method_start, next_start - 1))
+
+    if cursor <= len(lines):
+        segments.append(Segment("module", None, cursor, len(lines)))
+    return segments
+
+
+def _pattern_segments(lines: List[str], language: str) -> List[Segment]:
+    patterns = [
+        (re.compile(pattern), kind) for pattern, kind in DEFINITION_PATTERNS[language]
+    ]
+
+    starts: List[Tuple[int, str, str]] = []
+    for number, line in enumerate(lines, 1):
+        # Only top-level definitions, nested ones stay with their parent
+        if not line or line[0].isspace():
+            continue
+        for pattern, kind in patterns:
+            match = pattern.match(line)
+            if match:
+                name = match.group("name").strip()
+                receiver = match.groupdict().get("receiver")
+                if receiver:
+                    # func (s *Server) Start() is Server.Start
+                    name = f"{receiver.split()[-1].lstrip('*')}.{name}"
+                starts.append((number, kind, name))
+                break
+
+    segments = []
+    cursor = 1
+    for i, (number, kind, name) in enumerate(starts):
+        start = _leading_start(lines, number, cursor - 1)
+        if cursor < start:
+            segments.append(Segment("module", None, cursor, start - 1))
+        end = len(lines)
+        if i + 1 < len(starts):
+            end = _leading_start(lines, starts[i + 1][0], number) - 1
+        segments.append(Segment(kind, name, start, end))
+        cursor = end + 1
+
+    if cursor <= len(lines):
+        segments.append(Segment("module", None, cursor, len(lines)))
+    return segments
+
+
+def segment_code(source: str, language: str, max_tokens: int) -> List[Segment]:
+    """
+    Split source code into top-level definitions and the module level
+    code between them. Python is parsed with ast, other languages in
+    DEFINITION_PATTERNS are matched line by line.
+
+    Parameters:
+        source: The source code
+        language: The source language
+        max_tokens: Python classes over this size are split into methods
+
+    Returns:
+        The segments in file order, without blank ones
+    """
+    lines = split_lines(source)
+    segments = [Segment("module", None, 1, len(lines))]
+    if language == "python":
+        try:
+            segments = _python_segments(lines, source, max_tokens)
+        except SyntaxError as e:
+            logger.warning(f"Could not parse Python source, chunking by lines: {e}")
+    elif language in DEFINITION_PATTERNS:
+        segments = _pattern_segments(lines, language)
+
+    trimmed = [_trim(lines, segment) for segment in segments]
+    return [segment for segment in trimmed if segment]
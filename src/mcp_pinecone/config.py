@@ -0,0 +1,671 @@
+import argparse
+import dataclasses
+import logging
+import os
+import tomllib
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from dotenv import load_dotenv
+
+from .constants import (
+    DEFAULT_ALPHA,
+    DEFAULT_BLOB_PATH,
+    DEFAULT_CACHE_MAX_SIZE_MB,
+    DEFAULT_CACHE_PATH,
+    CHUNKING_STRATEGIES,
+    DEFAULT_CHUNK_HEADERS,
+    DEFAULT_CHUNK_MIN_TOKENS,
+    DEFAULT_CHUNK_OVERLAP_TOKENS,
+    DEFAULT_CHUNKING_STRATEGY,
+    DEFAULT_CLOUD,
+    DEFAULT_CONFIG_PATH,
+    DEFAULT_DATABASE_PATH,
+    DEFAULT_DELETION_PROTECTION,
+    DEFAULT_INDEX_NAME,
+    DEFAULT_INLINE_TEXT_MAX_BYTES,
+    DEFAULT_METRIC,
+    DEFAULT_MODEL_LIMITS,
+    DEFAULT_PREVIEW_CHARS,
+    DEFAULT_REGION,
+    DEFAULT_RERANK_CANDIDATES,
+    DEFAULT_SPARSE_MODEL,
+    DEFAULT_TOP_K,
+    EMBEDDERS,
+    INFERENCE_DIMENSION,
+    INFERENCE_MODEL,
+    INFERENCE_MODEL_LIMITS,
+    METRICS,
+    RERANKERS,
+    SPARSE_ENCODERS,
+    VECTOR_BACKENDS,
+)
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass
+class PineconeConfig:
+    """
+    Everything PineconeClient needs to reach an index
+    """
+
+    api_key: Optional[str] = None
+    index_name: str = DEFAULT_INDEX_NAME
+    model: str = INFERENCE_MODEL
+    dimension: int = INFERENCE_DIMENSION
+    # One of EMBEDDERS, defaults to pinecone for the pinecone backend
+    # and hashing for the local backends
+    embedder: Optional[str] = None
+    metric: str = DEFAULT_METRIC
+    cloud: str = DEFAULT_CLOUD
+    region: str = DEFAULT_REGION
+    deletion_protection: str = DEFAULT_DELETION_PROTECTION
+    # Data plane URL of the index, resolved with describe_index when unset
+    host: Optional[str] = None
+    # Control plane URL, e.g. Pinecone Local, defaults to the Pinecone cloud
+    control_plane_host: Optional[str] = None
+
+    @property
+    def is_local(self) -> bool:
+        """
+        Whether the client targets a custom endpoint rather than the Pinecone cloud.
+        """
+        return bool(self.host or self.control_plane_host)
+
+
+@dataclass
+class ChunkingConfig:
+    """
+    How documents are split before embedding
+    """
+
+    # One of CHUNKING_STRATEGIES
+    strategy: str = DEFAULT_CHUNKING_STRATEGY
+    headers: List[str] = field(default_factory=lambda: list(DEFAULT_CHUNK_HEADERS))
+    # Estimated tokens per chunk, defaults to the embedding model's input limit
+    max_tokens: Optional[int] = None
+    min_tokens: int = DEFAULT_CHUNK_MIN_TOKENS
+    overlap_tokens: int = DEFAULT_CHUNK_OVERLAP_TOKENS
+    # Embed chunks after their document title and header path
+    breadcrumbs: bool = False
+    # Per namespace overrides of the settings above, e.g.
+    # {"meetings": {"strategy": "paragraph"}}
+    namespaces: Dict[str, Dict[str, Any]] = field(default_factory=dict)
+
+
+@dataclass
+class CacheConfig:
+    """
+    On-disk cache of embeddings keyed by model, input type and text hash
+    """
+
+    enabled: bool = True
+    path: str = DEFAULT_CACHE_PATH
+    max_size_mb: int = DEFAULT_CACHE_MAX_SIZE_MB
+
+
+@dataclass
+class BlobConfig:
+    """
+    Content-addressed storage for text and payloads too large for metadata
+    """
+
+    path: str = DEFAULT_BLOB_PATH
+    # Chunk text over this many bytes is stored as a blob
+    inline_max_bytes: int = DEFAULT_INLINE_TEXT_MAX_BYTES
+    # Characters of blob text kept in metadata for search results
+    preview_chars: int = DEFAULT_PREVIEW_CHARS
+
+
+@dataclass
+class SparseConfig:
+    """
+    Sparse vectors stored next to dense ones for hybrid search
+    """
+
+    # One of SPARSE_ENCODERS, hybrid search is off when unset
+    encoder: Optional[str] = None
+    # Used by the pinecone encoder
+    model: str = DEFAULT_SPARSE_MODEL
+    # Default weight of dense scores, the sparse weight is 1 - alpha
+    alpha: float = DEFAULT_ALPHA
+
+
+@dataclass
+class RerankConfig:
+    """
+    Second stage ordering of semantic-search results with a cross-encoder
+    """
+
+    # Default of the semantic-search rerank argument
+    enabled: bool = False
+    # One of RERANKERS, defaults to pinecone for the pinecone backend
+    # and local for the local backends
+    provider: Optional[str] = None
+    # Defaults to the provider's entry in DEFAULT_RERANK_MODELS
+    model: Optional[str] = None
+    # Candidates fetched from the vector store before reranking
+    candidates: int = DEFAULT_RERANK_CANDIDATES
+
+
+@dataclass
+class ServerConfig:
+    """
+    Configuration for the MCP server and its vector store backend
+    """
+
+    pinecone: PineconeConfig = field(default_factory=PineconeConfig)
+    chunking: ChunkingConfig = field(default_factory=ChunkingConfig)
+    cache: CacheConfig = field(default_factory=CacheConfig)
+    blobs: BlobConfig = field(default_factory=BlobConfig)
+    sparse: SparseConfig = field(default_factory=SparseConfig)
+    rerank: RerankConfig = field(default_factory=RerankConfig)
+    backend: str = "pinecone"
+    database_path: str = DEFAULT_DATABASE_PATH
+    top_k: int = DEFAULT_TOP_K
+
+    @property
+    def embedder_provider(self) -> str:
+        """
+        The embedding provider, falling back to one that suits the backend.
+        """
+        if self.pinecone.embedder:
+            return self.pinecone.embedder
+        return "pinecone" if self.backend == "pinecone" else "hashing"
+
+    @property
+    def rerank_provider(self) -> str:
+        """
+        The reranker, falling back to one that suits the backend and, without
+        an API key for the Inference API, a local one.
+        """
+        if self.rerank.provider:
+            return self.rerank.provider
+        if self.backend == "pinecone" and self.pinecone.api_key:
+            return "pinecone"
+        return "local"
+
+    @property
+    def chunk_max_tokens(self) -> int:
+        """
+        The chunk size limit, falling back to what the embedding model accepts.
+        """
+        if self.chunking.max_tokens is not None:
+            return self.chunking.max_tokens
+        limits = INFERENCE_MODEL_LIMITS.get(self.pinecone.model, DEFAULT_MODEL_LIMITS)
+        return limits["max_tokens_per_input"]
+
+    def validate(self):
+        """
+        Check the configuration is usable, raising ValueError if not.
+        """
+        if self.backend not in VECTOR_BACKENDS:
+            raise ValueError(
+                f"Unknown backend: {self.backend}. Expected one of: {', '.join(VECTOR_BACKENDS)}"
+            )
+
+        # Only the Pinecone backend talks to the cloud
+        if (
+            self.backend == "pinecone"
+            and not self.pinecone.api_key
+            and not self.pinecone.is_local
+        ):
+            raise ValueError(
+                "Pinecone API key is required. Provide it via --api-key argument or PINECONE_API_KEY environment variable"
+            )
+
+        if self.embedder_provider not in EMBEDDERS:
+            raise ValueError(
+                f"Unknown embedder: {self.embedder_provider}. Expected one of: {', '.join(EMBEDDERS)}"
+            )
+
+        if self.pinecone.metric not in METRICS:
+            raise ValueError(
+                f"Unknown metric: {self.pinecone.metric}. Expected one of: {', '.join(METRICS)}"
+            )
+
+        if self.sparse.encoder:
+            if self.sparse.encoder not in SPARSE_ENCODERS:
+                raise ValueError(
+                    f"Unknown sparse encoder: {self.sparse.encoder}. Expected one of: {', '.join(SPARSE_ENCODERS)}"
+                )
+            # Pinecone only accepts sparse values on dotproduct indexes
+            if self.backend == "pinecone" and self.pinecone.metric != "dotproduct":
+                raise ValueError(
+                    "Hybrid search with the pinecone backend requires metric = \"dotproduct\""
+                )
+
+        if not 0.0 <= self.sparse.alpha <= 1.0:
+            raise ValueError(
+                f"sparse.alpha must be between 0 and 1, got: {self.sparse.alpha}"
+            )
+
+        if self.rerank_provider not in RERANKERS:
+            raise ValueError(
+                f"Unknown reranker: {self.rerank_provider}. Expected one of: {', '.join(RERANKERS)}"
+            )
+
+        # Pinecone Local and other custom endpoints do not serve inference
+        for setting, provider in (
+            ("embedder", self.embedder_provider),
+            ("sparse encoder", self.sparse.encoder),
+            ("reranker", self.rerank_provider),
+        ):
+            if provider == "pinecone" and not self.pinecone.api_key:
+                raise ValueError(
+                    f"The pinecone {setting} uses the Pinecone Inference API, which requires an API key. Provide it via --api-key argument or PINECONE_API_KEY environment variable, or choose another {setting}"
+                )
+
+        if self.rerank.candidates < 1:
+            raise ValueError(
+                f"rerank.candidates must be positive, got: {self.rerank.candidates}"
+            )
+
+        if self.pinecone.deletion_protection not in ("enabled", "disabled"):
+            raise ValueError(
+                f"deletion_protection must be 'enabled' or 'disabled', got: {self.pinecone.deletion_protection}"
+            )
+
+        if self.pinecone.dimension < 1:
+            raise ValueError(
+                f"dimension must be positive, got: {self.pinecone.dimension}"
+            )
+
+        if self.cache.max_size_mb < 1:
+            raise ValueError(
+                f"cache.max_size_mb must be positive, got: {self.cache.max_size_mb}"
+            )
+
+        if self.blobs.inline_max_bytes < 1 or self.blobs.preview_chars < 0:
+            raise ValueError(
+                "blobs.inline_max_bytes must be positive and blobs.preview_chars not negative"
+            )
+
+        if self.top_k < 1:
+            raise ValueError(f"top_k must be positive, got: {self.top_k}")
+
+        if not self.chunking.headers or any(
+            not header or set(header) != {"#"} for header in self.chunking.headers
+        ):
+            raise ValueError(
+                f"chunking.headers must be markdown header markers, got: {self.chunking.headers}"
+            )
+
+        for namespace, settings in self.chunking.namespaces.items():
+            if not isinstance(settings, dict):
+                raise ValueError(
+                    f"chunking.namespaces.{namespace} must be a table, got: {settings!r}"
+                )
+        strategies = [self.chunking.strategy] + [
+            settings.get("strategy", self.chunking.strategy)
+            for settings in self.chunking.namespaces.values()
+        ]
+        for strategy in strategies:
+            if strategy not in CHUNKING_STRATEGIES:
+                raise ValueError(
+                    f"Unknown chunking strategy: {strategy}. Expected one of: {', '.join(CHUNKING_STRATEGIES)}"
+                )
+
+        if self.chunk_max_tokens < 1:
+            raise ValueError(
+                f"chunking.max_tokens must be positive, got: {self.chunk_max_tokens}"
+            )
+
+        if not 0 <= self.chunking.min_tokens < self.chunk_max_tokens:
+            raise ValueError(
+                f"chunking.min_tokens must be between 0 and max_tokens, got: {self.chunking.min_tokens}"
+            )
+
+        # Overlap must leave room for new text in every chunk
+        if not 0 <= self.chunking.overlap_tokens <= self.chunk_max_tokens // 2:
+            raise ValueError(
+                f"chunking.overlap_tokens must be between 0 and half of max_tokens, got: {self.chunking.overlap_tokens}"
+            )
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "ServerConfig":
+        """
+        Build a configuration from merged config file, environment and CLI values.
+
+        Parameters:
+            data: Nested dict mirroring the dataclass fields.
+
+        Returns:
+            ServerConfig: The configuration, not yet validated.
+        """
+        return _from_dict(cls, data, "")
+
+
+def _from_dict(cls, data: Dict[str, Any], section: str):
+    if not isinstance(data, dict):
+        raise ValueError(f"Expected a table for [{section}], got: {data!r}")
+
+    known = {f.name: f for f in dataclasses.fields(cls)}
+    unknown = set(data) - set(known)
+    if unknown:
+        where = f" in [{section}]" if section else ""
+        raise ValueError(
+            f"Unknown config keys{where}: {', '.join(sorted(unknown))}"
+        )
+
+    kwargs = {}
+    for name, value in data.items():
+        key = f"{section}.{name}".lstrip(".")
+        factory = known[name].default_factory
+        default = known[name].default
+        if factory is not dataclasses.MISSING and dataclasses.is_dataclass(factory):
+            value = _from_dict(factory, value, key)
+        elif factory is not dataclasses.MISSING:
+            if not isinstance(value, type(factory())):
+                raise ValueError(f"Config key {key} has the wrong type: {value!r}")
+        elif default not in (dataclasses.MISSING, None):
+            # TOML writes 1.0 as 1 just as readily
+            if isinstance(default, float) and type(value) is int:
+                value = float(value)
+            if not isinstance(value, type(default)):
+                raise ValueError(f"Config key {key} has the wrong type: {value!r}")
+        kwargs[name] = value
+    return cls(**kwargs)
+
+
+def _merge(base: Dict[str, Any], override: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Recursively merge override into base, later layers win.
+    """
+    merged = dict(base)
+    for key, value in override.items():
+        if isinstance(value, dict) and isinstance(merged.get(key), dict):
+            merged[key] = _merge(merged[key], value)
+        else:
+            merged[key] = value
+    return merged
+
+
+def _prune(layer: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Drop unset values so they do not override lower layers.
+    Empty strings count as unset, as left behind by blank .env entries.
+    """
+    pruned = {}
+    for key, value in layer.items():
+        if isinstance(value, dict):
+            value = _prune(value)
+            if not value:
+                continue
+        elif value is None or value == "":
+            continue
+        pruned[key] = value
+    return pruned
+
+
+def _env_int(name: str) -> Optional[int]:
+    value = os.getenv(name)
+    if value is None or value == "":
+        return None
+    try:
+        return int(value)
+    except ValueError:
+        raise ValueError(
+            f"Environment variable {name} must be an integer, got: {value}"
+        )
+
+
+def read_config_file(path: Optional[str], profile: Optional[str]) -> Dict[str, Any]:
+    """
+    Read the TOML config file and apply the selected profile over its top level.
+
+    Parameters:
+        path: Path to the config file, the default location is optional.
+        profile: Optional name of a [profiles.<name>] table.
+
+    Returns:
+        Dict[str, Any]: The settings from the file, empty if there is no file.
+    """
+    explicit = path is not None
+    path = os.path.expanduser(path or DEFAULT_CONFIG_PATH)
+
+    if not os.path.exists(path):
+        if explicit:
+            raise ValueError(f"Config file not found: {path}")
+        if profile:
+            raise ValueError(
+                f"Profile {profile} requested but no config file found at {path}"
+            )
+        return {}
+
+    with open(path, "rb") as f:
+        try:
+            data = tomllib.load(f)
+        except tomllib.TOMLDecodeError as e:
+            raise ValueError(f"Invalid config file {path}: {e}")
+
+    profiles = data.pop("profiles", {})
+    if not profile:
+        return data
+
+    if profile not in profiles:
+        available = ", ".join(sorted(profiles)) or "none"
+        raise ValueError(
+            f"Unknown profile {profile} in {path}. Available: {available}"
+        )
+
+    logger.info(f"Using profile {profile} from {path}")
+    return _merge(data, profiles[profile])
+
+
+def build_parser() -> argparse.ArgumentParser:
+    parser = argparse.ArgumentParser(description="Pinecone MCP Configuration")
+    parser.add_argument(
+        "--config",
+        default=None,
+        help=f"TOML config file. Will use environment variable MCP_PINECONE_CONFIG if not provided, defaulting to {DEFAULT_CONFIG_PATH}.",
+    )
+    parser.add_argument(
+        "--profile",
+        default=None,
+        help="Named profile from the config file. Will use environment variable MCP_PINECONE_PROFILE if not provided.",
+    )
+    parser.add_argument(
+        "--index-name",
+        default=None,
+        help="Name of the Pinecone index to use. Will use environment variable PINECONE_INDEX_NAME if not provided.",
+    )
+    parser.add_argument(
+        "--api-key",
+        default=None,
+        help="API key for Pinecone. Will use environment variable PINECONE_API_KEY if not provided.",
+    )
+    parser.add_argument(
+        "--host",
+        default=None,
+        help="Data plane URL of the index, e.g. a Pinecone Local container. Will use environment variable PINECONE_HOST if not provided.",
+    )
+    parser.add_argument(
+        "--control-plane-host",
+        default=None,
+        help="Control plane URL used to check and create the index. Will use environment variable PINECONE_CONTROL_PLANE_HOST if not provided.",
+    )
+    parser.add_argument(
+        "--model",
+        default=None,
+        help="Inference model used for embeddings. Will use environment variable PINECONE_INFERENCE_MODEL if not provided.",
+    )
+    parser.add_argument(
+        "--embedder",
+        default=None,
+        choices=EMBEDDERS,
+        help="Embedding provider. Will use environment variable PINECONE_EMBEDDER if not provided, defaulting to pinecone for the pinecone backend and hashing otherwise.",
+    )
+    parser.add_argument(
+        "--dimension",
+        type=int,
+        default=None,
+        help="Embedding dimension of the index. Will use environment variable PINECONE_INFERENCE_DIMENSION if not provided.",
+    )
+    parser.add_argument(
+        "--metric",
+        default=None,
+        choices=METRICS,
+        help="Distance metric for new indexes. Will use environment variable PINECONE_METRIC if not provided.",
+    )
+    parser.add_argument(
+        "--sparse-encoder",
+        default=None,
+        choices=SPARSE_ENCODERS,
+        help="Store sparse vectors for hybrid search. Will use environment variable MCP_PINECONE_SPARSE_ENCODER if not provided.",
+    )
+    parser.add_argument(
+        "--rerank",
+        action="store_true",
+        dest="rerank_enabled",
+        default=None,
+        help="Rerank semantic-search results unless a call sets rerank to false.",
+    )
+    parser.add_argument(
+        "--reranker",
+        default=None,
+        choices=RERANKERS,
+        help="Reranking provider. Will use environment variable MCP_PINECONE_RERANKER if not provided, defaulting to pinecone for the pinecone backend and local otherwise.",
+    )
+    parser.add_argument(
+        "--rerank-model",
+        default=None,
+        help="Rerank model. Will use environment variable MCP_PINECONE_RERANK_MODEL if not provided.",
+    )
+    parser.add_argument(
+        "--cloud",
+        default=None,
+        help="Cloud for new serverless indexes. Will use environment variable PINECONE_CLOUD if not provided.",
+    )
+    parser.add_argument(
+        "--region",
+        default=None,
+        help="Region for new serverless indexes. Will use environment variable PINECONE_REGION if not provided.",
+    )
+    parser.add_argument(
+        "--deletion-protection",
+        default=None,
+        choices=("enabled", "disabled"),
+        help="Deletion protection for new indexes. Will use environment variable PINECONE_DELETION_PROTECTION if not provided.",
+    )
+    parser.add_argument(
+        "--backend",
+        default=None,
+        choices=VECTOR_BACKENDS,
+        help="Vector store backend to use. Will use environment variable MCP_PINECONE_BACKEND if not provided, defaulting to pinecone.",
+    )
+    parser.add_argument(
+        "--database-path",
+        default=None,
+        help="SQLite database file for the sqlite backend. Will use environment variable MCP_PINECONE_DATABASE_PATH if not provided.",
+    )
+    parser.add_argument(
+        "--top-k",
+        type=int,
+        default=None,
+        help="Default number of semantic-search results. Will use environment variable MCP_PINECONE_TOP_K if not provided.",
+    )
+    parser.add_argument(
+        "--cache-path",
+        default=None,
+        help="Embedding cache file. Will use environment variable MCP_PINECONE_CACHE_PATH if not provided.",
+    )
+    parser.add_argument(
+        "--blob-path",
+        default=None,
+        help="Directory for chunk text and payloads too large for metadata. Will use environment variable MCP_PINECONE_BLOB_PATH if not provided.",
+    )
+    parser.add_argument(
+        "--no-embedding-cache",
+        action="store_false",
+        dest="cache_enabled",
+        default=None,
+        help="Always call the embedder instead of reusing cached embeddings.",
+    )
+    return parser
+
+
+def load_config(argv: Optional[List[str]] = None) -> ServerConfig:
+    """
+    Load configuration, in increasing order of precedence, from built-in defaults,
+    the config file, the selected profile, environment variables and CLI flags.
+    Only the entry point calls this, so importing the package has no side effects.
+
+    Parameters:
+        argv: Command line arguments, defaults to sys.argv[1:].
+
+    Returns:
+        ServerConfig: The validated configuration.
+    """
+    load_dotenv()
+    args = build_parser().parse_args(argv)
+
+    file_layer = read_config_file(
+        args.config or os.getenv("MCP_PINECONE_CONFIG"),
+        args.profile or os.getenv("MCP_PINECONE_PROFILE"),
+    )
+
+    env_layer = {
+        "pinecone": {
+            "api_key": os.getenv("PINECONE_API_KEY"),
+            "index_name": os.getenv("PINECONE_INDEX_NAME"),
+            "host": os.getenv("PINECONE_HOST"),
+            "control_plane_host": os.getenv("PINECONE_CONTROL_PLANE_HOST"),
+            "model": os.getenv("PINECONE_INFERENCE_MODEL"),
+            "dimension": _env_int("PINECONE_INFERENCE_DIMENSION"),
+            "embedder": os.getenv("PINECONE_EMBEDDER"),
+            "metric": os.getenv("PINECONE_METRIC"),
+            "cloud": os.getenv("PINECONE_CLOUD"),
+            "region": os.getenv("PINECONE_REGION"),
+            "deletion_protection": os.getenv("PINECONE_DELETION_PROTECTION"),
+        },
+        "backend": os.getenv("MCP_PINECONE_BACKEND"),
+        "database_path": os.getenv("MCP_PINECONE_DATABASE_PATH"),
+        "top_k": _env_int("MCP_PINECONE_TOP_K"),
+        "cache": {"path": os.getenv("MCP_PINECONE_CACHE_PATH")},
+        "blobs": {"path": os.getenv("MCP_PINECONE_BLOB_PATH")},
+        "sparse": {"encoder": os.getenv("MCP_PINECONE_SPARSE_ENCODER")},
+        "rerank": {
+            "provider": os.getenv("MCP_PINECONE_RERANKER"),
+            "model": os.getenv("MCP_PINECONE_RERANK_MODEL"),
+        },
+    }
+
+    cli_layer = {
+        "pinecone": {
+            "api_key": args.api_key,
+            "index_name": args.index_name,
+            "host": args.host,
+            "control_plane_host": args.control_plane_host,
+            "model": args.model,
+            "dimension": args.dimension,
+            "embedder": args.embedder,
+            "metric": args.metric,
+            "cloud": args.cloud,
+            "region": args.region,
+            "deletion_protection": args.deletion_protection,
+        },
+        "backend": args.backend,
+        "database_path": args.database_path,
+        "top_k": args.top_k,
+        "cache": {"path": args.cache_path, "enabled": args.cache_enabled},
+        "blobs": {"path": args.blob_path},
+        "sparse": {"encoder": args.sparse_encoder},
+        "rerank": {
+            "enabled": args.rerank_enabled,
+            "provider": args.reranker,
+            "model": args.rerank_model,
+        },
+    }
+
+    data = {}
+    for layer in (file_layer, env_layer, cli_layer):
+        data = _merge(data, _prune(layer))
+
+    if not data.get("pinecone", {}).get("index_name"):
+        logger.info(f"No index name provided, using default: {DEFAULT_INDEX_NAME}")
+
+    config = ServerConfig.from_dict(data)
+    config.validate()
+    return config
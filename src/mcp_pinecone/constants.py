@@ -1,52 +1,7 @@
-# Index name
 import os
-import argparse
-from dotenv import load_dotenv
-
-load_dotenv()
-
-
-def get_pinecone_config():
-    parser = argparse.ArgumentParser(description="Pinecone MCP Configuration")
-    parser.add_argument(
-        "--index-name",
-        default=None,
-        help="Name of the Pinecone index to use. Will use environment variable PINECONE_INDEX_NAME if not provided.",
-    )
-    parser.add_argument(
-        "--api-key",
-        default=None,
-        help="API key for Pinecone. Will use environment variable PINECONE_API_KEY if not provided.",
-    )
-    args = parser.parse_args()
-
-    # Use command line arguments if provided, otherwise fall back to environment variables
-    index_name = args.index_name or os.getenv("PINECONE_INDEX_NAME")
-    api_key = args.api_key or os.getenv("PINECONE_API_KEY")
-
-    # Set default index name if none provided
-    if not index_name:
-        index_name = "mcp-pinecone-index"
-        print(f"No index name provided, using default: {index_name}")
-
-    # Validate API key
-    if not api_key:
-        raise ValueError(
-            "Pinecone API key is required. Provide it via --api-key argument or PINECONE_API_KEY environment variable"
-        )
-
-    return index_name, api_key
-
-
-# Get configuration values
-PINECONE_INDEX_NAME, PINECONE_API_KEY = get_pinecone_config()
-
-# Validate configuration after loading
-if not PINECONE_INDEX_NAME or not PINECONE_API_KEY:
-    raise ValueError(
-        "Missing required configuration. Ensure PINECONE_INDEX_NAME and PINECONE_API_KEY "
-        "are set either via environment variables or command line arguments."
-    )
+
+# Default index name
+DEFAULT_INDEX_NAME = "mcp-pinecone-index"
 
 # Inference API model name
 INFERENCE_MODEL = "multilingual-e5-large"
@@ -54,10 +9,162 @@ INFERENCE_MODEL = "multilingual-e5-large"
 # Inference API embedding dimension
 INFERENCE_DIMENSION = 1024
 
+# Default serverless spec for new indexes
+DEFAULT_CLOUD = "aws"
+DEFAULT_REGION = "us-east-1"
+
+# Deletion protection for new indexes, "enabled" or "disabled"
+DEFAULT_DELETION_PROTECTION = "disabled"
+
+# Default number of results returned by semantic-search
+DEFAULT_TOP_K = 10
+
+# Chunking strategies selectable per upsert or per namespace
+CHUNKING_STRATEGIES = ("markdown", "fixed", "sentence", "paragraph", "none", "code")
+DEFAULT_CHUNKING_STRATEGY = "markdown"
+
+# Markdown headers documents are split on
+DEFAULT_CHUNK_HEADERS = ["#", "##", "###"]
+
+# Chunks below this many estimated tokens are merged into a neighbour
+DEFAULT_CHUNK_MIN_TOKENS = 50
+
+# Estimated tokens repeated from the end of a chunk at the start of the next
+DEFAULT_CHUNK_OVERLAP_TOKENS = 50
+
+# API key sent to Pinecone Local, which does not check it
+LOCAL_API_KEY = "pclocal"
+
+# Per request limits of the Pinecone Inference API embedding models
+INFERENCE_MODEL_LIMITS = {
+    "multilingual-e5-large": {"max_inputs": 96, "max_tokens_per_input": 507},
+    "llama-text-embed-v2": {"max_inputs": 96, "max_tokens_per_input": 2048},
+    "pinecone-sparse-english-v0": {"max_inputs": 96, "max_tokens_per_input": 512},
+}
+
+# Limits assumed for models missing from INFERENCE_MODEL_LIMITS
+DEFAULT_MODEL_LIMITS = {"max_inputs": 96, "max_tokens_per_input": 507}
+
+# Upper bound on estimated tokens sent in one embedding request
+EMBED_BATCH_MAX_TOKENS = 16_000
+
+# Number of vectors sent per upsert request
+UPSERT_BATCH_SIZE = 100
+
+# Number of IDs per fetch request, fetch IDs travel in the URL
+FETCH_BATCH_SIZE = 100
+
+# Number of IDs per delete request, Pinecone's maximum
+DELETE_BATCH_SIZE = 1000
+
+# Default number of documents per list-documents page
+DEFAULT_LIST_LIMIT = 50
+
+# Pinecone's metadata limit per record, including the stored text
+METADATA_MAX_BYTES = 40 * 1024
+
+# Metadata keys whose ISO dates are stored as epoch seconds for range filters,
+# other fields such as titles and headers keep dates as written
+DATE_FIELDS = ("date", "created", "updated", "modified", "published")
+
+# Content-addressed store for chunk text and binary payloads
+DEFAULT_BLOB_PATH = os.path.join("database", "blobs")
+
+# Chunk text over this size goes to the blob store, metadata keeps a preview
+DEFAULT_INLINE_TEXT_MAX_BYTES = 16 * 1024
+DEFAULT_PREVIEW_CHARS = 1000
+
+# On-disk embedding cache
+DEFAULT_CACHE_PATH = os.path.join("~", ".cache", "mcp-pinecone", "embeddings.db")
+DEFAULT_CACHE_MAX_SIZE_MB = 512
+
+# Supported embedding providers
+EMBEDDERS = ("pinecone", "local", "hashing")
+
+# Pinecone Inference model names mapped to their sentence-transformers equivalents
+LOCAL_MODEL_ALIASES = {
+    "multilingual-e5-large": "intfloat/multilingual-e5-large",
+}
+
+# Distance metrics for new indexes, hybrid search needs dotproduct
+METRICS = ("cosine", "dotproduct", "euclidean")
+DEFAULT_METRIC = "cosine"
+
+# Supported sparse encoders for hybrid search
+SPARSE_ENCODERS = ("bm25", "pinecone")
+
+# Pinecone hosted sparse embedding model
+DEFAULT_SPARSE_MODEL = "pinecone-sparse-english-v0"
+
+# Weight of dense against sparse scores in hybrid search, 1.0 is dense only
+DEFAULT_ALPHA = 0.5
+
+# Supported rerankers for semantic-search
+RERANKERS = ("pinecone", "local")
+
+# Default model per reranker, a Pinecone hosted model or a sentence-transformers
+# cross-encoder
+DEFAULT_RERANK_MODELS = {
+    "pinecone": "bge-reranker-v2-m3",
+    "local": "cross-encoder/ms-marco-MiniLM-L-6-v2",
+}
+
+# Candidates fetched from the vector store before reranking
+DEFAULT_RERANK_CANDIDATES = 50
+
+# Documents per Pinecone rerank request
+RERANK_MAX_DOCUMENTS = 100
+
+# Supported vector store backends
+VECTOR_BACKENDS = ("pinecone", "memory", "sqlite")
+
+# Default SQLite database for the sqlite backend
+DEFAULT_DATABASE_PATH = os.path.join("database", "mcp-pinecone.db")
+
+# Config file read when --config and MCP_PINECONE_CONFIG are not set
+DEFAULT_CONFIG_PATH = os.path.join("~", ".config", "mcp-pinecone", "config.toml")
+
 # Export values for use in other modules
 __all__ = [
-    "PINECONE_INDEX_NAME",
-    "PINECONE_API_KEY",
+    "DEFAULT_INDEX_NAME",
     "INFERENCE_MODEL",
     "INFERENCE_DIMENSION",
+    "DEFAULT_CLOUD",
+    "DEFAULT_REGION",
+    "DEFAULT_DELETION_PROTECTION",
+    "DEFAULT_TOP_K",
+    "LOCAL_API_KEY",
+    "CHUNKING_STRATEGIES",
+    "DEFAULT_CHUNKING_STRATEGY",
+    "DEFAULT_CHUNK_HEADERS",
+    "DEFAULT_CHUNK_MIN_TOKENS",
+    "DEFAULT_CHUNK_OVERLAP_TOKENS",
+    "INFERENCE_MODEL_LIMITS",
+    "DEFAULT_MODEL_LIMITS",
+    "EMBED_BATCH_MAX_TOKENS",
+    "UPSERT_BATCH_SIZE",
+    "FETCH_BATCH_SIZE",
+    "DELETE_BATCH_SIZE",
+    "DEFAULT_LIST_LIMIT",
+    "METADATA_MAX_BYTES",
+    "DATE_FIELDS",
+    "DEFAULT_BLOB_PATH",
+    "DEFAULT_INLINE_TEXT_MAX_BYTES",
+    "DEFAULT_PREVIEW_CHARS",
+    "DEFAULT_CACHE_PATH",
+    "DEFAULT_CACHE_MAX_SIZE_MB",
+    "EMBEDDERS",
+    "LOCAL_MODEL_ALIASES",
+    "METRICS",
+    "DEFAULT_METRIC",
+    "SPARSE_ENCODERS",
+    "DEFAULT_SPARSE_MODEL",
+    "DEFAULT_ALPHA",
+    "RERANKERS",
+    "DEFAULT_RERANK_MODELS",
+    "DEFAULT_RERANK_CANDIDATES",
+    "RERANK_MAX_DOCUMENTS",
+    "VECTOR_BACKENDS",
+    "DEFAULT_DATABASE_PATH",
+    "DEFAULT_CONFIG_PATH",
 ]
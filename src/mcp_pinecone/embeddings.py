@@ -0,0 +1,304 @@
+import hashlib
+import logging
+import math
+import os
+import re
+import time
+from typing import Iterator, List, Optional, Protocol
+
+from .config import PineconeConfig, ServerConfig
+from .constants import (
+    DEFAULT_MODEL_LIMITS,
+    EMBED_BATCH_MAX_TOKENS,
+    EMBEDDERS,
+    INFERENCE_MODEL_LIMITS,
+    LOCAL_MODEL_ALIASES,
+)
+from .utils import estimate_tokens
+
+logger = logging.getLogger(__name__)
+
+TOKEN_PATTERN = re.compile(r"\w+", re.UNICODE)
+
+# Attempts per embedding request when rate limited
+MAX_RETRIES = 5
+
+
+def batch_texts(
+    texts: List[str],
+    max_inputs: int,
+    max_tokens_per_input: int,
+    max_batch_tokens: int = EMBED_BATCH_MAX_TOKENS,
+) -> Iterator[List[str]]:
+    """
+    Split texts into consecutive batches within per request limits.
+
+    Parameters:
+        texts: The texts to split, order is preserved.
+        max_inputs: Maximum number of texts per batch.
+        max_tokens_per_input: Tokens counted per text, longer ones are truncated.
+        max_batch_tokens: Maximum estimated tokens per batch.
+
+    Returns:
+        Iterator[List[str]]: The batches.
+    """
+    batch: List[str] = []
+    batch_tokens = 0
+    for text in texts:
+        tokens = min(estimate_tokens(text), max_tokens_per_input)
+        if batch and (
+            len(batch) >= max_inputs or batch_tokens + tokens > max_batch_tokens
+        ):
+            yield batch
+            batch, batch_tokens = [], 0
+        batch.append(text)
+        batch_tokens += tokens
+    if batch:
+        yield batch
+
+
+class Embedder(Protocol):
+    """
+    Turns text into dense vectors of a fixed dimension.
+    Asymmetric models such as e5 embed queries and passages differently,
+    so callers must say which one they have.
+    """
+
+    dimension: int
+
+    def embed_query(self, text: str) -> List[float]: ...
+
+    def embed_passages(self, texts: List[str]) -> List[List[float]]: ...
+
+
+class PineconeEmbedder:
+    """
+    Generates embeddings with the Pinecone Inference API.
+    """
+
+    def __init__(self, config: PineconeConfig):
+        from .pinecone import pinecone_client
+
+        self.model = config.model
+        self.dimension = config.dimension
+        self.limits = INFERENCE_MODEL_LIMITS.get(config.model, DEFAULT_MODEL_LIMITS)
+        self.pc = pinecone_client(config)
+
+    def embed_query(self, text: str) -> List[float]:
+        """
+        Generate embeddings for a search query using Pinecone Inference API.
+
+        Parameters:
+            text: The query to generate embeddings for.
+
+        Returns:
+            List[float]: The embeddings for the query.
+        """
+        return self._embed([text], "query")[0]
+
+    def embed_passages(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate embeddings for documents using Pinecone Inference API.
+        Texts are sent in as few requests as the model's limits allow.
+
+        Parameters:
+            texts: The passages to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The embeddings, in the same order as texts.
+        """
+        embeddings = []
+        for batch in batch_texts(
+            texts, self.limits["max_inputs"], self.limits["max_tokens_per_input"]
+        ):
+            embeddings.extend(self._embed(batch, "passage"))
+        return embeddings
+
+    def _embed(self, texts: List[str], input_type: str) -> List[List[float]]:
+        for attempt in range(MAX_RETRIES):
+            try:
+                response = self.pc.inference.embed(
+                    model=self.model,
+                    inputs=texts,
+                    parameters={"input_type": input_type, "truncate": "END"},
+                )
+                break
+            except Exception as e:
+                # Back off and retry when rate limited
+                if getattr(e, "status", None) != 429 or attempt == MAX_RETRIES - 1:
+                    raise
+                delay = 2**attempt
+                logger.warning(f"Rate limited by Inference API, retrying in {delay}s")
+                time.sleep(delay)
+
+        # if the response is empty, raise an error
+        if not response.data or len(response.data) != len(texts):
+            raise ValueError(f"Failed to generate embeddings for texts: {texts}")
+        return [item.values for item in response.data]
+
+
+class SentenceTransformerEmbedder:
+    """
+    Generates embeddings on the local CPU with sentence-transformers.
+    Text never leaves the machine, the model is downloaded once and cached.
+    """
+
+    def __init__(self, model: str, device: str = "cpu"):
+        try:
+            from sentence_transformers import SentenceTransformer
+        except ImportError:
+            raise ImportError(
+                "The local embedder requires sentence-transformers. "
+                "Install it with: uv pip install sentence-transformers"
+            )
+
+        self.model_name = LOCAL_MODEL_ALIASES.get(model, model)
+        logger.info(f"Loading local embedding model {self.model_name}")
+        self.model = SentenceTransformer(self.model_name, device=device)
+        self.dimension = self.model.get_sentence_embedding_dimension()
+
+        # e5 models are trained with these prefixes and lose recall without them
+        if "e5" in self.model_name.lower():
+            self.query_prefix, self.passage_prefix = "query: ", "passage: "
+        else:
+            self.query_prefix, self.passage_prefix = "", ""
+
+    def embed_query(self, text: str) -> List[float]:
+        """
+        Generate embeddings for a search query with the local model.
+
+        Parameters:
+            text: The query to generate embeddings for.
+
+        Returns:
+            List[float]: The normalized embeddings for the query.
+        """
+        return self.model.encode(
+            self.query_prefix + text, normalize_embeddings=True
+        ).tolist()
+
+    def embed_passages(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate embeddings for documents with the local model.
+
+        Parameters:
+            texts: The passages to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The normalized embeddings, in the same order as texts.
+        """
+        return self.model.encode(
+            [self.passage_prefix + text for text in texts], normalize_embeddings=True
+        ).tolist()
+
+
+class HashingEmbedder:
+    """
+    Generates deterministic embeddings by hashing tokens into buckets.
+    This needs no network access or model, so it only captures lexical
+    overlap. Meant for tests and offline development.
+    """
+
+    def __init__(self, dimension: int):
+        self.dimension = dimension
+
+    def embed_query(self, text: str) -> List[float]:
+        """
+        Generate embeddings for a search query, the same as for a passage.
+
+        Parameters:
+            text: The query to generate embeddings for.
+
+        Returns:
+            List[float]: The normalized embeddings for the query.
+        """
+        return self.embed(text)
+
+    def embed_passages(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate embeddings for documents.
+
+        Parameters:
+            texts: The passages to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The normalized embeddings, in the same order as texts.
+        """
+        return [self.embed(text) for text in texts]
+
+    def embed(self, text: str) -> List[float]:
+        """
+        Generate embeddings for a given text.
+
+        Parameters:
+            text: The text to generate embeddings for.
+
+        Returns:
+            List[float]: The normalized embeddings for the text, all zeros
+            when it has no word tokens.
+        """
+        vector = [0.0] * self.dimension
+        for token in TOKEN_PATTERN.findall(text.lower()):
+            digest = hashlib.blake2b(token.encode("utf-8"), digest_size=8).digest()
+            bucket = int.from_bytes(digest[:4], "little") % self.dimension
+            sign = 1.0 if digest[4] & 1 else -1.0
+            vector[bucket] += sign
+
+        norm = math.sqrt(sum(v * v for v in vector))
+        if not norm:
+            # Text such as "---" or an emoji has no tokens, a zero vector
+            # matches nothing instead of failing the whole upsert
+            return vector
+        return [v / norm for v in vector]
+
+
+def create_embedder(config: ServerConfig) -> Embedder:
+    """
+    Create the configured embedder and check it matches the index dimension.
+
+    Parameters:
+        config: The server configuration.
+
+    Returns:
+        Embedder: The embedder instance.
+    """
+    provider = config.embedder_provider
+    if provider == "pinecone":
+        embedder = PineconeEmbedder(config.pinecone)
+    elif provider == "local":
+        embedder = SentenceTransformerEmbedder(config.pinecone.model)
+    elif provider == "hashing":
+        embedder = HashingEmbedder(config.pinecone.dimension)
+    else:
+        raise ValueError(
+            f"Unknown embedder: {provider}. Expected one of: {', '.join(EMBEDDERS)}"
+        )
+
+    validate_dimension(embedder, config.pinecone.dimension)
+
+    # Hashing is cheaper than a cache lookup
+    if config.cache.enabled and provider != "hashing":
+        from .cache import CachedEmbedder, EmbeddingCache
+
+        cache = EmbeddingCache(
+            os.path.expanduser(config.cache.path), config.cache.max_size_mb
+        )
+        model = f"{provider}:{config.pinecone.model}:{embedder.dimension}"
+        embedder = CachedEmbedder(embedder, cache, model=model)
+
+    return embedder
+
+
+def validate_dimension(embedder: Embedder, dimension: Optional[int]):
+    """
+    Raise if an embedder would produce vectors the index cannot store.
+
+    Parameters:
+        embedder: The embedder to check.
+        dimension: The dimension of the index, skipped if unknown.
+    """
+    if dimension is not None and embedder.dimension != dimension:
+        raise ValueError(
+            f"Embedder {type(embedder).__name__} produces {embedder.dimension}-dimensional "
+            f"vectors but the index expects {dimension}. Set dimension to match the model."
+        )
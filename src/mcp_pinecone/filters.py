@@ -0,0 +1,171 @@
+import re
+from typing import Any, Dict, List, Optional, Union
+
+from .metadata import parse_iso_date
+
+
+# Comparison operators supported by Pinecone metadata filters
+COMPARISON_OPERATORS = {"$eq", "$ne", "$gt", "$gte", "$lt", "$lte", "$in", "$nin"}
+
+# Logical operators supported by Pinecone metadata filters
+LOGICAL_OPERATORS = {"$and", "$or"}
+
+# Metadata field date_range compares against, epoch seconds once upserted
+DATE_FIELD = "date"
+
+# Dates without a time, which as a range end cover the whole day
+DATE_ONLY_PATTERN = re.compile(r"\d{4}-\d{2}-\d{2}")
+
+SECONDS_PER_DAY = 24 * 60 * 60
+
+
+def _parse_date(field: str, value: Any) -> int:
+    epoch = parse_iso_date(value) if isinstance(value, str) else None
+    if epoch is None:
+        raise ValueError(f"date_range.{field} must be an ISO 8601 date, got: {value}")
+    return epoch
+
+
+def build_search_filter(
+    category: Optional[str] = None,
+    tags: Optional[Union[str, List[str]]] = None,
+    date_range: Optional[Dict[str, str]] = None,
+    filter: Optional[Dict[str, Any]] = None,
+) -> Optional[Dict[str, Any]]:
+    """
+    Translate semantic-search arguments into a Pinecone metadata filter.
+
+    Parameters:
+        category: Optional category the records must have.
+        tags: Optional tag or tags, records must have at least one of them.
+        date_range: Optional start and end ISO dates, both inclusive,
+            compared against the epoch seconds of the date field.
+        filter: Optional raw filter, combined with the others.
+
+    Returns:
+        Optional[Dict[str, Any]]: The filter, None if nothing is filtered on.
+    """
+    conditions = []
+    if category:
+        conditions.append({"category": {"$eq": category}})
+    if tags:
+        if isinstance(tags, str):
+            tags = [tags]
+        if not isinstance(tags, list) or not all(isinstance(t, str) for t in tags):
+            raise ValueError(f"tags must be a list of strings, got: {tags}")
+        conditions.append({"tags": {"$in": tags}})
+
+    if date_range:
+        if not isinstance(date_range, dict):
+            raise ValueError(f"date_range must be an object, got: {date_range}")
+        bounds = {}
+        start, end = date_range.get("start"), date_range.get("end")
+        if start:
+            bounds["$gte"] = _parse_date("start", start)
+        if end:
+            bounds["$lte"] = _parse_date("end", end)
+            if DATE_ONLY_PATTERN.fullmatch(end):
+                bounds["$lte"] += SECONDS_PER_DAY - 1
+        if bounds:
+            conditions.append({DATE_FIELD: bounds})
+
+    if filter:
+        if not isinstance(filter, dict):
+            raise ValueError(f"filter must be an object, got: {filter}")
+        conditions.append(filter)
+
+    if not conditions:
+        return None
+    if len(conditions) == 1:
+        return conditions[0]
+    return {"$and": conditions}
+
+
+def matches_filter(metadata: Dict[str, Any], filter: Optional[Dict[str, Any]]) -> bool:
+    """
+    Evaluate a Pinecone-style metadata filter against a record's metadata.
+    Used by the local backends to mirror Pinecone's query semantics.
+
+    Parameters:
+        metadata: The metadata of the record.
+        filter: The filter to apply, e.g. {"genre": {"$in": ["drama"]}}.
+
+    Returns:
+        bool: True if the metadata satisfies the filter, False otherwise.
+    """
+    if not filter:
+        return True
+
+    for key, condition in filter.items():
+        if key == "$and":
+            if not all(matches_filter(metadata, sub) for sub in condition):
+                return False
+        elif key == "$or":
+            if not any(matches_filter(metadata, sub) for sub in condition):
+                return False
+        elif key.startswith("$"):
+            raise ValueError(f"Unsupported filter operator: {key}")
+        elif not _matches_field(metadata, key, condition):
+            return False
+
+    return True
+
+
+def _matches_field(metadata: Dict[str, Any], field: str, condition: Any) -> bool:
+    # A bare value is shorthand for $eq
+    if not isinstance(condition, dict):
+        condition = {"$eq": condition}
+
+    for operator, operand in condition.items():
+        if operator == "$exists":
+            if (field in metadata) != bool(operand):
+                return False
+            continue
+
+        if operator not in COMPARISON_OPERATORS:
+            raise ValueError(f"Unsupported filter operator: {operator}")
+
+        if field not in metadata:
+            # Missing fields only satisfy negative operators
+            if operator in ("$ne", "$nin"):
+                continue
+            return False
+
+        value = metadata[field]
+        # Pinecone matches list-valued metadata if any element matches
+        values = value if isinstance(value, list) else [value]
+
+        if operator in ("$ne", "$nin"):
+            positive = "$eq" if operator == "$ne" else "$in"
+            if any(_compare(v, positive, operand) for v in values):
+                return False
+        elif not any(_compare(v, operator, operand) for v in values):
+            return False
+
+    return True
+
+
+def _compare(value: Any, operator: str, operand: Any) -> bool:
+    if operator == "$eq":
+        return value == operand
+    if operator == "$in":
+        return value in _as_list(operand)
+    try:
+        if operator == "$gt":
+            return value > operand
+        if operator == "$gte":
+            return value >= operand
+        if operator == "$lt":
+            return value < operand
+        if operator == "$lte":
+            return value <= operand
+    except TypeError:
+        # Pinecone ignores range comparisons against non-numeric values
+        return False
+    raise ValueError(f"Unsupported filter operator: {operator}")
+
+
+def _as_list(operand: Any) -> List[Any]:
+    if not isinstance(operand, list):
+        raise ValueError(f"Expected a list for $in/$nin, got: {operand!r}")
+    return operand
@@ -0,0 +1,282 @@
+import logging
+import math
+from typing import Any, Dict, List, Optional, Union
+
+from .constants import INFERENCE_DIMENSION
+from .embeddings import Embedder, HashingEmbedder
+from .filters import matches_filter
+from .pinecone import PineconeRecord
+from .sparse import SparseEncoder, encode_records, hybrid_score
+
+logger = logging.getLogger(__name__)
+
+
+def cosine_similarity(a: List[float], b: List[float]) -> float:
+    """
+    Compute the cosine similarity between two vectors.
+
+    Parameters:
+        a: The first vector.
+        b: The second vector.
+
+    Returns:
+        float: The cosine similarity, 0.0 if either vector has no magnitude.
+    """
+    dot = sum(x * y for x, y in zip(a, b))
+    norm_a = math.sqrt(sum(x * x for x in a))
+    norm_b = math.sqrt(sum(y * y for y in b))
+    if not norm_a or not norm_b:
+        return 0.0
+    return dot / (norm_a * norm_b)
+
+
+class InMemoryVectorStore:
+    """
+    A vector store that keeps records in process memory.
+    Useful for offline development and CI, nothing survives a restart.
+    """
+
+    def __init__(
+        self,
+        dimension: int = INFERENCE_DIMENSION,
+        embedder: Optional[Embedder] = None,
+        sparse_encoder: Optional[SparseEncoder] = None,
+    ):
+        self.dimension = dimension
+        self.embedder = embedder or HashingEmbedder(dimension)
+        self.sparse_encoder = sparse_encoder
+        # namespace -> record id -> {"id", "values", "metadata"}
+        self.namespaces: Dict[str, Dict[str, Dict[str, Any]]] = {}
+
+    def generate_embeddings(self, text: str) -> List[float]:
+        """
+        Generate passage embeddings for a given text with the configured embedder.
+
+        Parameters:
+            text: The text to generate embeddings for.
+
+        Returns:
+            List[float]: The embeddings for the text.
+        """
+        return self.embedder.embed_passages([text])[0]
+
+    def generate_embeddings_batch(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate passage embeddings for many texts in as few calls as possible.
+
+        Parameters:
+            texts: The texts to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The embeddings, in the same order as texts.
+        """
+        return self.embedder.embed_passages(texts)
+
+    def upsert_records(
+        self,
+        records: List[PineconeRecord],
+        namespace: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Upsert records into memory.
+
+        Parameters:
+            records: List of records to upsert.
+            namespace: Optional namespace to upsert into.
+
+        Returns:
+            Dict[str, Any]: The number of records upserted.
+        """
+        vectors = self.namespaces.setdefault(namespace or "", {})
+        upserted = 0
+        sparse_vectors = encode_records(self.sparse_encoder, records)
+        for record, sparse_values in zip(records, sparse_vectors):
+            # Don't continue if there's no vector values
+            if not record.embedding:
+                continue
+
+            if len(record.embedding) != self.dimension:
+                raise ValueError(
+                    f"Vector dimension {len(record.embedding)} does not match "
+                    f"store dimension {self.dimension}"
+                )
+
+            vectors[record.id] = {
+                "id": record.id,
+                "values": list(record.embedding),
+                "metadata": {**record.metadata, "text": record.text},
+                "sparse_values": sparse_values,
+            }
+            upserted += 1
+
+        return {"upserted_count": upserted}
+
+    def search_records(
+        self,
+        query: Union[str, List[float]],
+        top_k: int = 10,
+        namespace: Optional[str] = None,
+        filter: Optional[Dict] = None,
+        include_metadata: bool = True,
+        alpha: Optional[float] = None,
+    ) -> Dict[str, Any]:
+        """
+        Search records by cosine similarity.
+
+        Parameters:
+            query: The query to search for.
+            top_k: The number of results to return.
+            namespace: Optional namespace to search in.
+            filter: Optional filter to apply to the search.
+            include_metadata: Whether to include metadata in the search results.
+            alpha: Optional weight of dense against sparse scores for hybrid
+                search, 1.0 is dense only. Needs a sparse encoder and a text query.
+
+        Returns:
+            Dict[str, Any]: The matches, best first.
+        """
+        if isinstance(query, str):
+            vector = self.embedder.embed_query(query)
+        else:
+            vector = query
+
+        sparse_query = None
+        if alpha is not None and self.sparse_encoder and isinstance(query, str):
+            sparse_query = self.sparse_encoder.encode_query(query)
+
+        vectors = self.namespaces.get(namespace or "", {})
+        matches = []
+        for record in vectors.values():
+            if not matches_filter(record["metadata"], filter):
+                continue
+            score = hybrid_score(
+                cosine_similarity(vector, record["values"]),
+                sparse_query,
+                record["sparse_values"],
+                alpha,
+            )
+            match = {"id": record["id"], "score": score}
+            if include_metadata:
+                match["metadata"] = dict(record["metadata"])
+            matches.append(match)
+
+        matches.sort(key=lambda m: m["score"], reverse=True)
+        return {"matches": matches[:top_k], "namespace": namespace or ""}
+
+    def delete_records(
+        self, ids: List[str], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Delete records by ID
+
+        Parameters:
+            ids: List of record IDs to delete
+            namespace: Optional namespace to delete from
+        """
+        vectors = self.namespaces.get(namespace or "", {})
+        for record_id in ids:
+            vectors.pop(record_id, None)
+        return {}
+
+    def update_metadata(
+        self, record_id: str, metadata: Dict[str, Any], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Set metadata fields of a record, keeping its vector and other fields
+
+        Parameters:
+            record_id: ID of the record to update
+            metadata: The fields to set
+            namespace: Optional namespace of the record
+        """
+        record = self.namespaces.get(namespace or "", {}).get(record_id)
+        if record is not None:
+            record["metadata"].update(metadata)
+        return {}
+
+    def fetch_records(
+        self, ids: List[str], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Fetch specific records by ID
+
+        Parameters:
+            ids: List of record IDs to fetch
+            namespace: Optional namespace to fetch from
+
+        Returns:
+            Dict[str, Any]: The records found, keyed by ID.
+        """
+        vectors = self.namespaces.get(namespace or "", {})
+        return {
+            "vectors": {
+                record_id: {
+                    "id": record_id,
+                    "values": list(vectors[record_id]["values"]),
+                    "metadata": dict(vectors[record_id]["metadata"]),
+                }
+                for record_id in ids
+                if record_id in vectors
+            },
+            "namespace": namespace or "",
+        }
+
+    def describe_index(self) -> Dict[str, Any]:
+        """
+        Describe the store and count its vectors per namespace.
+
+        Returns:
+            Dict[str, Any]: The name, dimension, metric, host, total_vector_count,
+            index_fullness and namespaces with their vector_count.
+        """
+        namespaces = {
+            namespace: {"vector_count": len(vectors)}
+            for namespace, vectors in self.namespaces.items()
+            if vectors
+        }
+        return {
+            "name": "memory",
+            "dimension": self.dimension,
+            "metric": "cosine",
+            "host": None,
+            "total_vector_count": sum(
+                namespace["vector_count"] for namespace in namespaces.values()
+            ),
+            # Memory is the only limit
+            "index_fullness": 0.0,
+            "namespaces": namespaces,
+        }
+
+    def list_records(
+        self,
+        prefix: Optional[str] = None,
+        limit: int = 100,
+        namespace: Optional[str] = None,
+        pagination_token: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        List records in memory, ordered by ID.
+
+        Parameters:
+            prefix: Optional prefix to filter records by.
+            limit: The number of records to return per page.
+            namespace: Optional namespace to list records from.
+            pagination_token: Optional token of the previous page to continue from.
+        """
+        vectors = self.namespaces.get(namespace or "", {})
+        # The token is the last ID of the previous page
+        ids = sorted(
+            record_id
+            for record_id in vectors
+            if (not prefix or record_id.startswith(prefix))
+            and (not pagination_token or record_id > pagination_token)
+        )
+        page = ids[:limit]
+        return {
+            "vectors": [
+                {"id": record_id, "metadata": dict(vectors[record_id]["metadata"])}
+                for record_id in page
+            ],
+            "namespace": namespace or "",
+            "pagination_token": page[-1] if len(ids) > limit else None,
+        }
@@ -0,0 +1,242 @@
+import json
+import logging
+import math
+import re
+from datetime import date, datetime, time, timezone
+from typing import Any, Dict, Optional, Tuple
+
+import yaml
+
+from .blobs import BlobStore
+from .constants import DATE_FIELDS, METADATA_MAX_BYTES
+
+logger = logging.getLogger(__name__)
+
+# Nested keys are joined into one, {"author": {"name": ...}} becomes "author.name"
+KEY_SEPARATOR = "."
+
+# 2024-05-01, 2024-05-01T10:30, 2024-05-01 10:30:00Z, 2024-05-01T10:30:00+02:00
+ISO_DATE_PATTERN = re.compile(
+    r"\d{4}-\d{2}-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?)?"
+)
+
+
+# YAML front-matter between --- lines at the very start of a document
+FRONT_MATTER_PATTERN = re.compile(
+    r"\A\ufeff?---[ \t]*\r?\n(.*?)\r?\n(?:---|\.\.\.)[ \t]*(?:\r?\n|\Z)", re.DOTALL
+)
+
+# Obsidian Dataview style inline fields on their own line, e.g. "status:: draft"
+# The space after :: keeps C++ and Rust paths such as std::vector out
+INLINE_FIELD_PATTERN = re.compile(
+    r"^([A-Za-z_][\w -]*?)::[ \t]+(\S.*?)[ \t]*$", re.MULTILINE
+)
+
+# Front-matter keys Jekyll allows as space separated strings
+LIST_KEYS = ("tags", "categories")
+
+
+class MetadataError(ValueError):
+    """Raised when metadata cannot be stored, naming the field at fault"""
+
+    def __init__(self, field: str, message: str):
+        self.field = field
+        super().__init__(f"Invalid metadata field '{field}': {message}")
+
+
+def parse_iso_date(value: str) -> Optional[int]:
+    """
+    Convert an ISO 8601 date or datetime to epoch seconds.
+    Dates without a timezone are taken as UTC.
+
+    Parameters:
+        value: The string to convert.
+
+    Returns:
+        Optional[int]: The epoch seconds, None if the string is not an ISO date.
+    """
+    if not ISO_DATE_PATTERN.fullmatch(value):
+        return None
+    try:
+        return to_epoch(datetime.fromisoformat(value))
+    except ValueError:
+        return None
+
+
+def to_epoch(value: date) -> int:
+    """
+    Convert a date or datetime to epoch seconds, naive values taken as UTC.
+    """
+    if not isinstance(value, datetime):
+        value = datetime.combine(value, time())
+    if value.tzinfo is None:
+        value = value.replace(tzinfo=timezone.utc)
+    return int(value.timestamp())
+
+
+def extract_front_matter(content: str) -> Tuple[Dict[str, Any], str]:
+    """
+    Split YAML front-matter, as used by Obsidian and Jekyll, off a document
+    and collect inline "key:: value" fields from its body.
+
+    Parameters:
+        content: The document text.
+
+    Returns:
+        Tuple[Dict[str, Any], str]: The metadata found, and the content without
+        its front-matter. Inline fields stay in the content.
+    """
+    metadata: Dict[str, Any] = {}
+    match = FRONT_MATTER_PATTERN.match(content)
+    if match:
+        try:
+            front_matter = yaml.safe_load(match.group(1)) or {}
+        except yaml.YAMLError as e:
+            raise MetadataError("front-matter", f"invalid YAML: {e}")
+        if not isinstance(front_matter, dict):
+            raise MetadataError("front-matter", "expected key: value pairs")
+        metadata.update(front_matter)
+        content = content[match.end() :]
+
+    for key, value in INLINE_FIELD_PATTERN.findall(content):
+        metadata.setdefault(key.strip(), value)
+
+    for key in LIST_KEYS:
+        if isinstance(metadata.get(key), str):
+            metadata[key] = metadata[key].replace(",", " ").split()
+
+    return metadata, content
+
+
+def normalize_metadata(metadata: Dict[str, Any], prefix: str = "") -> Dict[str, Any]:
+    """
+    Coerce metadata into what Pinecone accepts: strings, numbers, booleans
+    and lists of strings. Nested objects are flattened, ISO dates in
+    DATE_FIELDS become epoch seconds, numbers and booleans in lists become
+    strings and null values are dropped.
+
+    Parameters:
+        metadata: The metadata to normalize.
+        prefix: Key prefix of nested objects, empty at the top level.
+
+    Returns:
+        Dict[str, Any]: The normalized metadata.
+    """
+    if not isinstance(metadata, dict):
+        raise MetadataError(prefix or "metadata", "expected an object")
+
+    normalized: Dict[str, Any] = {}
+    for key, value in metadata.items():
+        field = f"{prefix}{key}"
+        if not isinstance(key, str) or not key:
+            raise MetadataError(field, "keys must be non-empty strings")
+
+        if value is None:
+            continue
+        if isinstance(value, dict):
+            normalized.update(normalize_metadata(value, f"{field}{KEY_SEPARATOR}"))
+            continue
+        if isinstance(value, (list, tuple)):
+            normalized[field] = _normalize_list(field, value)
+            continue
+        normalized[field] = _normalize_scalar(field, value, key in DATE_FIELDS)
+
+    return normalized
+
+
+def _normalize_scalar(field: str, value: Any, is_date: bool) -> Any:
+    if isinstance(value, bool):
+        return value
+    # YAML front-matter parses dates itself, "title: 2024-05-01" included
+    if isinstance(value, date):
+        return to_epoch(value) if is_date else value.isoformat()
+    if isinstance(value, (int, float)):
+        if isinstance(value, float) and not math.isfinite(value):
+            raise MetadataError(field, f"numbers must be finite, got {value}")
+        return value
+    if isinstance(value, str):
+        epoch = parse_iso_date(value) if is_date else None
+        return value if epoch is None else epoch
+    raise MetadataError(
+        field, f"unsupported type {type(value).__name__}, use a string or number"
+    )
+
+
+def _normalize_list(field: str, values: Any) -> list:
+    items = []
+    for i, item in enumerate(values):
+        if item is None:
+            continue
+        if isinstance(item, bool):
+            items.append(str(item).lower())
+        elif isinstance(item, (str, int, float)):
+            items.append(str(item))
+        else:
+            raise MetadataError(
+                f"{field}[{i}]", "lists may only contain strings, numbers or booleans"
+            )
+    return items
+
+
+def metadata_size(metadata: Dict[str, Any]) -> int:
+    """
+    Approximate size of metadata as Pinecone counts it, in bytes of JSON.
+    """
+    encoded = json.dumps(metadata, separators=(",", ":"), ensure_ascii=False)
+    return len(encoded.encode("utf-8"))
+
+
+def fit_metadata(
+    record_id: str,
+    metadata: Dict[str, Any],
+    text: str,
+    max_bytes: int = METADATA_MAX_BYTES,
+    blob_store: Optional[BlobStore] = None,
+) -> Dict[str, Any]:
+    """
+    Normalize a record's metadata and add its text. Large text is moved to
+    the blob store, leaving a text_ref and a preview, and text is truncated
+    when the record would still exceed Pinecone's per record metadata limit.
+
+    Parameters:
+        record_id: The record the metadata belongs to, for error messages.
+        metadata: The record's metadata.
+        text: The record's text, stored under "text".
+        max_bytes: The metadata size limit.
+        blob_store: Optional blob store for text over its inline_max_bytes.
+
+    Returns:
+        Dict[str, Any]: Metadata that fits in the limit.
+    """
+    metadata = normalize_metadata(metadata)
+    text_size = len(text.encode("utf-8"))
+    if blob_store and text_size > blob_store.inline_max_bytes:
+        ref = blob_store.put_text(text)
+        metadata = {**metadata, "text_ref": ref, "text_size": text_size}
+        text = text[: blob_store.preview_chars]
+    fitted = {**metadata, "text": text}
+    size = metadata_size(fitted)
+    if size <= max_bytes:
+        return fitted
+
+    without_text = metadata_size({**metadata, "text": ""})
+    if without_text > max_bytes:
+        largest = max(metadata, key=lambda key: metadata_size({key: metadata[key]}))
+        raise MetadataError(
+            largest,
+            f"record {record_id} has {without_text} bytes of metadata besides its "
+            f"text, over the {max_bytes} byte limit. This field is the largest",
+        )
+
+    # JSON escaping can grow text, so trim the encoded overflow and re-measure
+    encoded = text.encode("utf-8")
+    while size > max_bytes:
+        encoded = encoded[: len(encoded) - (size - max_bytes)]
+        text = encoded.decode("utf-8", errors="ignore")
+        encoded = text.encode("utf-8")
+        size = metadata_size({**metadata, "text": text})
+    logger.warning(
+        f"Truncated text of record {record_id} to {len(encoded)} bytes "
+        f"to fit the {max_bytes} byte metadata limit"
+    )
+    return {**metadata, "text": text}
@@ -1,18 +1,15 @@
-from pinecone import Pinecone, ServerlessSpec, FetchResponse
+from pinecone import Pinecone, ServerlessSpec
 from typing import List, Dict, Any, Optional, Union
 
 from pydantic import BaseModel
-from .constants import (
-    INFERENCE_DIMENSION,
-    PINECONE_INDEX_NAME,
-    PINECONE_API_KEY,
-    INFERENCE_MODEL,
-)
-from dotenv import load_dotenv
+from .blobs import BlobStore
+from .config import PineconeConfig
+from .constants import LOCAL_API_KEY, UPSERT_BATCH_SIZE
+from .embeddings import Embedder, PineconeEmbedder, validate_dimension
+from .metadata import fit_metadata
+from .sparse import SparseEncoder, encode_records, scale_sparse
 import logging
 
-load_dotenv()
-
 logger = logging.getLogger(__name__)
 
 
@@ -22,6 +19,25 @@ class PineconeRecord(BaseModel):
     embedding: List[float]
     text: str
     metadata: Dict[str, Any]
+    # {"indices": [...], "values": [...]} for hybrid search
+    sparse_values: Optional[Dict[str, List]] = None
+
+
+def pinecone_client(config: PineconeConfig) -> Pinecone:
+    """
+    Create a Pinecone SDK client for the configured endpoint.
+
+    Parameters:
+        config: The Pinecone configuration.
+
+    Returns:
+        Pinecone: The client, for the control plane host if one is set.
+    """
+    # e.g. Pinecone Local, which accepts any API key
+    api_key = config.api_key or LOCAL_API_KEY
+    if config.control_plane_host:
+        return Pinecone(api_key=api_key, host=config.control_plane_host)
+    return Pinecone(api_key=api_key)
 
 
 class PineconeClient:
@@ -29,15 +45,49 @@ class PineconeClient:
     A client for interacting with Pinecone.
     """
 
-    def __init__(self):
-        self.pc = Pinecone(api_key=PINECONE_API_KEY)
-        # Initialize index after checking/creating
-        self.ensure_index_exists()
-        desc = self.pc.describe_index(PINECONE_INDEX_NAME)
-        self.index = self.pc.Index(
-            name=PINECONE_INDEX_NAME,
-            host=desc.host,  # Get the proper host from the index description
-        )
+    def __init__(
+        self,
+        config: PineconeConfig,
+        embedder: Optional[Embedder] = None,
+        sparse_encoder: Optional[SparseEncoder] = None,
+        blob_store: Optional[BlobStore] = None,
+    ):
+        self.config = config
+        self.embedder = embedder or PineconeEmbedder(config)
+        self.sparse_encoder = sparse_encoder
+        # Holds text too large for metadata, which is truncated without one
+        self.blob_store = blob_store
+        self.pc = pinecone_client(config)
+
+        host = config.host
+        if host and not config.control_plane_host:
+            # A fixed data plane without a control plane, nothing to check or create
+            logger.info(f"Using index host {host}, skipping index checks")
+        else:
+            # Initialize index after checking/creating
+            self.ensure_index_exists()
+            if not host:
+                desc = self.pc.describe_index(config.index_name)
+                host = self.resolve_host(desc.host)
+                # Catch model changes before they corrupt the index
+                validate_dimension(self.embedder, desc.dimension)
+        self.index = self.pc.Index(name=config.index_name, host=host)
+
+    def resolve_host(self, host: str) -> str:
+        """
+        Local control planes report bare host:port pairs, which the SDK
+        would otherwise reach over https.
+
+        Parameters:
+            host: The host from the index description.
+
+        Returns:
+            str: The host with the control plane's scheme when it has none.
+        """
+        control_plane = self.config.control_plane_host
+        if "://" in host or not control_plane or "://" not in control_plane:
+            return host
+        return f"{control_plane.split('://')[0]}://{host}"
 
     def ensure_index_exists(self):
         """
@@ -46,9 +96,9 @@ class PineconeClient:
         try:
             indexes = self.pc.list_indexes()
 
-            exists = any(index["name"] == PINECONE_INDEX_NAME for index in indexes)
+            exists = any(index["name"] == self.config.index_name for index in indexes)
             if exists:
-                logger.warning(f"Index {PINECONE_INDEX_NAME} already exists")
+                logger.warning(f"Index {self.config.index_name} already exists")
                 return
 
             self.create_index()
@@ -63,11 +113,11 @@ class PineconeClient:
         """
         try:
             return self.pc.create_index(
-                name=PINECONE_INDEX_NAME,
-                dimension=INFERENCE_DIMENSION,
-                metric="cosine",
-                deletion_protection="disabled",  # Consider enabling for production
-                spec=ServerlessSpec(cloud="aws", region="us-east-1"),
+                name=self.config.index_name,
+                dimension=self.config.dimension,
+                metric=self.config.metric,
+                deletion_protection=self.config.deletion_protection,
+                spec=ServerlessSpec(cloud=self.config.cloud, region=self.config.region),
             )
         except Exception as e:
             logger.error(f"Failed to create index: {e}")
@@ -75,7 +125,8 @@ class PineconeClient:
 
     def generate_embeddings(self, text: str) -> List[float]:
         """
-        Generate embeddings for a given text using Pinecone Inference API.
+        Generate passage embeddings for a given text with the configured
+        embedder, Pinecone Inference API by default.
 
         Parameters:
             text: The text to generate embeddings for.
@@ -83,15 +134,19 @@ class PineconeClient:
         Returns:
             List[float]: The embeddings for the text.
         """
-        response = self.pc.inference.embed(
-            model=INFERENCE_MODEL,
-            inputs=[text],
-            parameters={"input_type": "passage", "truncate": "END"},
-        )
-        # if the response is empty, raise an error
-        if not response.data:
-            raise ValueError(f"Failed to generate embeddings for text: {text}")
-        return response.data[0].values
+        return self.embedder.embed_passages([text])[0]
+
+    def generate_embeddings_batch(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate passage embeddings for many texts in as few calls as possible.
+
+        Parameters:
+            texts: The texts to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The embeddings, in the same order as texts.
+        """
+        return self.embedder.embed_passages(texts)
 
     def upsert_records(
         self,
@@ -110,21 +165,30 @@ class PineconeClient:
         """
         try:
             vectors = []
-            for record in records:
+            sparse_vectors = encode_records(self.sparse_encoder, records)
+            for record, sparse_values in zip(records, sparse_vectors):
                 # Don't continue if there's no vector values
                 if not record.embedding:
                     continue
 
-                vector_values = record.embedding
-                raw_text = record.text
-                record_id = record.id
-                metadata = record.metadata
-
-                # Add raw text to metadata
-                metadata["text"] = raw_text
-                vectors.append((record_id, vector_values, metadata))
-
-            return self.index.upsert(vectors=vectors, namespace=namespace)
+                vector = {
+                    "id": record.id,
+                    "values": record.embedding,
+                    # Add raw text to metadata, within Pinecone's size limit
+                    "metadata": fit_metadata(
+                        record.id,
+                        record.metadata,
+                        record.text,
+                        blob_store=self.blob_store,
+                    ),
+                }
+                if sparse_values:
+                    vector["sparse_values"] = sparse_values
+                vectors.append(vector)
+
+            return self.index.upsert(
+                vectors=vectors, namespace=namespace, batch_size=UPSERT_BATCH_SIZE
+            ).to_dict()
 
         except Exception as e:
             logger.error(f"Error upserting records: {e}")
@@ -137,6 +201,7 @@ class PineconeClient:
         namespace: Optional[str] = None,
         filter: Optional[Dict] = None,
         include_metadata: bool = True,
+        alpha: Optional[float] = None,
     ) -> Dict[str, Any]:
         """
         Search records using integrated inference.
@@ -147,24 +212,36 @@ class PineconeClient:
             namespace: Optional namespace to search in.
             filter: Optional filter to apply to the search.
             include_metadata: Whether to include metadata in the search results.
+            alpha: Optional weight of dense against sparse scores for hybrid
+                search, 1.0 is dense only. Needs a sparse encoder and a text query.
 
         Returns:
             Dict[str, Any]: The search results from Pinecone.
         """
         try:
-            # If query is text, use our custom function to get embeddings
+            # If query is text, embed it as a query rather than a passage
             if isinstance(query, str):
-                vector = self.generate_embeddings(query)
+                vector = self.embedder.embed_query(query)
             else:
                 vector = query
 
+            sparse_vector = None
+            if alpha is not None and self.sparse_encoder and isinstance(query, str):
+                sparse_vector = self.sparse_encoder.encode_query(query)
+                # Convex combination, Pinecone scores the weighted dot products
+                vector = [v * alpha for v in vector]
+                sparse_vector = scale_sparse(sparse_vector, 1 - alpha)
+                if not sparse_vector["indices"]:
+                    sparse_vector = None
+
             return self.index.query(
                 vector=vector,
+                sparse_vector=sparse_vector,
                 top_k=top_k,
                 namespace=namespace,
                 include_metadata=include_metadata,
                 filter=filter,
-            )
+            ).to_dict()
         except Exception as e:
             logger.error(f"Error searching records: {e}")
             raise
@@ -185,9 +262,28 @@ class PineconeClient:
             logger.error(f"Error deleting records: {e}")
             raise
 
+    def update_metadata(
+        self, record_id: str, metadata: Dict[str, Any], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Set metadata fields of a record, keeping its vector and other fields
+
+        Parameters:
+            record_id: ID of the record to update
+            metadata: The fields to set
+            namespace: Optional namespace of the record
+        """
+        try:
+            return self.index.update(
+                id=record_id, set_metadata=metadata, namespace=namespace
+            )
+        except Exception as e:
+            logger.error(f"Error updating metadata: {e}")
+            raise
+
     def fetch_records(
         self, ids: List[str], namespace: Optional[str] = None
-    ) -> FetchResponse:
+    ) -> Dict[str, Any]:
         """
         Fetch specific records by ID
 
@@ -196,22 +292,53 @@ class PineconeClient:
             namespace: Optional namespace to fetch from
 
         Returns:
-            FetchResponse: The response from Pinecone.
+            Dict[str, Any]: The response from Pinecone.
 
         Raises:
             Exception: If there is an error fetching the records.
         """
         try:
-            return self.index.fetch(ids=ids, namespace=namespace)
+            return self.index.fetch(ids=ids, namespace=namespace).to_dict()
         except Exception as e:
             logger.error(f"Error fetching records: {e}")
             raise
 
+    def describe_index(self) -> Dict[str, Any]:
+        """
+        Describe the index and count its vectors per namespace.
+
+        Returns:
+            Dict[str, Any]: The name, dimension, metric, host, total_vector_count,
+            index_fullness and namespaces with their vector_count.
+        """
+        try:
+            stats = self.index.describe_index_stats().to_dict()
+            description = {
+                "name": self.config.index_name,
+                "dimension": stats.get("dimension"),
+                "metric": self.config.metric,
+                "host": self.config.host,
+                "total_vector_count": stats.get("total_vector_count", 0),
+                "index_fullness": stats.get("index_fullness", 0.0),
+                "namespaces": stats.get("namespaces", {}),
+            }
+            # A fixed data plane without a control plane cannot describe itself
+            if not self.config.host or self.config.control_plane_host:
+                desc = self.pc.describe_index(self.config.index_name)
+                description.update(
+                    dimension=desc.dimension, metric=desc.metric, host=desc.host
+                )
+            return description
+        except Exception as e:
+            logger.error(f"Error describing index: {e}")
+            raise
+
     def list_records(
         self,
         prefix: Optional[str] = None,
         limit: int = 100,
         namespace: Optional[str] = None,
+        pagination_token: Optional[str] = None,
     ) -> Dict[str, Any]:
         """
         List records in the index using pagination.
@@ -220,17 +347,27 @@ class PineconeClient:
             prefix: Optional prefix to filter records by.
             limit: The number of records to return per page.
             namespace: Optional namespace to list records from.
+            pagination_token: Optional token of the previous page to continue from.
+
+        Raises:
+            Exception: If there is an error listing the records, since callers
+                deleting or reassembling documents must not mistake it for
+                an empty last page.
         """
         try:
             # Using list_paginated for single-page results
             response = self.index.list_paginated(
-                prefix=prefix, limit=limit, namespace=namespace
+                prefix=prefix,
+                limit=limit,
+                namespace=namespace,
+                pagination_token=pagination_token,
             )
 
             # Check if response is None
             if response is None:
-                logger.error("Received None response from Pinecone list_paginated")
-                return {"vectors": [], "namespace": namespace, "pagination_token": None}
+                raise RuntimeError(
+                    "Received None response from Pinecone list_paginated"
+                )
 
             # Handle the case where vectors might be None
             vectors = response.vectors if hasattr(response, "vectors") else []
@@ -250,5 +387,4 @@ class PineconeClient:
             }
         except Exception as e:
             logger.error(f"Error listing records: {e}")
-            # Return empty result instead of raising
-            return {"vectors": [], "namespace": namespace, "pagination_token": None}
+            raise
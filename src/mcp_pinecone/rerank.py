@@ -0,0 +1,133 @@
+import logging
+from typing import Any, Dict, List, Protocol
+
+from .config import ServerConfig
+from .constants import DEFAULT_RERANK_MODELS, RERANK_MAX_DOCUMENTS
+
+logger = logging.getLogger(__name__)
+
+
+class Reranker(Protocol):
+    """
+    Scores how well each document answers a query, reading both together.
+    Slower than vector search but much better at ordering a short list.
+    """
+
+    def score(self, query: str, documents: List[str]) -> List[float]: ...
+
+
+class PineconeReranker:
+    """
+    Reranks with a Pinecone hosted model such as bge-reranker-v2-m3.
+    """
+
+    def __init__(self, config: ServerConfig, model: str):
+        from .pinecone import pinecone_client
+
+        self.model = model
+        self.pc = pinecone_client(config.pinecone)
+
+    def score(self, query: str, documents: List[str]) -> List[float]:
+        """
+        Score documents against a query with the Pinecone Inference API.
+
+        Parameters:
+            query: The search query.
+            documents: The candidate documents.
+
+        Returns:
+            List[float]: The relevance scores, in the same order as documents.
+        """
+        scores = []
+        for start in range(0, len(documents), RERANK_MAX_DOCUMENTS):
+            batch = documents[start : start + RERANK_MAX_DOCUMENTS]
+            response = self.pc.inference.rerank(
+                model=self.model,
+                query=query,
+                documents=batch,
+                return_documents=False,
+                parameters={"truncate": "END"},
+            )
+            # Results come back best first, put them back in input order
+            batch_scores = [0.0] * len(batch)
+            for item in response.data:
+                batch_scores[item.index] = item.score
+            scores.extend(batch_scores)
+        return scores
+
+
+class CrossEncoderReranker:
+    """
+    Reranks on the local CPU with a sentence-transformers cross-encoder.
+    """
+
+    def __init__(self, model: str, device: str = "cpu"):
+        try:
+            from sentence_transformers import CrossEncoder
+        except ImportError:
+            raise ImportError(
+                "The local reranker requires sentence-transformers. "
+                "Install it with: uv pip install sentence-transformers"
+            )
+
+        logger.info(f"Loading local rerank model {model}")
+        self.model = CrossEncoder(model, device=device)
+
+    def score(self, query: str, documents: List[str]) -> List[float]:
+        """
+        Score documents against a query with the local model.
+
+        Parameters:
+            query: The search query.
+            documents: The candidate documents.
+
+        Returns:
+            List[float]: The relevance scores, in the same order as documents.
+        """
+        if not documents:
+            return []
+        scores = self.model.predict([(query, document) for document in documents])
+        return [float(score) for score in scores]
+
+
+def rerank_matches(
+    reranker: Reranker, query: str, matches: List[Dict[str, Any]], top_n: int
+) -> List[Dict[str, Any]]:
+    """
+    Reorder search matches by rerank score, keeping the vector score.
+
+    Parameters:
+        reranker: The reranker to score matches with.
+        query: The search query.
+        matches: Matches from search_records, with text in their metadata.
+        top_n: The number of matches to keep.
+
+    Returns:
+        List[Dict[str, Any]]: The best matches, each with a rerank_score.
+    """
+    documents = [match.get("metadata", {}).get("text", "") for match in matches]
+    scores = reranker.score(query, documents)
+    reranked = [
+        {**match, "rerank_score": score} for match, score in zip(matches, scores)
+    ]
+    reranked.sort(key=lambda m: m["rerank_score"], reverse=True)
+    return reranked[:top_n]
+
+
+def create_reranker(config: ServerConfig) -> Reranker:
+    """
+    Create the configured reranker.
+
+    Parameters:
+        config: The server configuration.
+
+    Returns:
+        Reranker: The reranker instance.
+    """
+    provider = config.rerank_provider
+    model = config.rerank.model or DEFAULT_RERANK_MODELS[provider]
+    if provider == "pinecone":
+        return PineconeReranker(config, model)
+    if provider == "local":
+        return CrossEncoderReranker(model)
+    raise ValueError(f"Unknown reranker: {provider}")
@@ -1,33 +1,72 @@
+import base64
+import binascii
 import logging
+import os
 from typing import Union, Sequence
 from mcp.server.models import InitializationOptions
 import mcp.types as types
 from mcp.server import NotificationOptions, Server
 from pydantic import AnyUrl
 import mcp.server.stdio
-from .pinecone import PineconeClient, PineconeRecord
+from .pinecone import PineconeRecord
+from .backends import (
+    VectorStore,
+    create_vector_store,
+    delete_record_ids,
+    fetch_metadata,
+    list_chunk_ids,
+    list_documents,
+)
+from .blobs import BlobStore, resolve_text
+from .config import ServerConfig
+from .constants import CHUNKING_STRATEGIES, DEFAULT_LIST_LIMIT
+from .rerank import Reranker, create_reranker, rerank_matches
 from .utils import MCPToolError
-from .chunking import MarkdownChunker
+from .metadata import extract_front_matter, normalize_metadata
+from .chunking import (
+    POSITION_METADATA_KEYS,
+    create_chunker,
+    document_metadata,
+    join_chunks,
+)
+from .filters import build_search_filter
 
 logging.basicConfig(level=logging.INFO)
 logger = logging.getLogger("pinecone-mcp")
 
-pinecone_client = None
+vector_store: VectorStore | None = None
+blob_store: BlobStore | None = None
+reranker: Reranker | None = None
+server_config = ServerConfig()
 server = Server("pinecone-mcp")
 
 
+def get_reranker() -> Reranker:
+    """
+    Create the reranker on first use, so a local model is only loaded when needed.
+    """
+    global reranker
+    if reranker is None:
+        reranker = create_reranker(server_config)
+    return reranker
+
+
 @server.list_resources()
 async def handle_list_resources() -> list[types.Resource]:
     try:
-        if pinecone_client is None:
-            logger.error("Pinecone client is not initialized")
+        if vector_store is None:
+            logger.error("Vector store is not initialized")
             return []
-        records = pinecone_client.list_records()
+        records = vector_store.list_records().get("vectors", [])
+
+        # Pinecone lists IDs only, so fetch the metadata naming each resource
+        missing = [record["id"] for record in records if not record.get("metadata")]
+        fetched = fetch_metadata(vector_store, missing) if missing else {}
 
         resources = []
-        for record in records.get("vectors", []):
+        for record in records:
             # If metadata is None, use empty dict
-            metadata = record.get("metadata") or {}
+            metadata = record.get("metadata") or fetched.get(record["id"], {})
             description = (
                 metadata.get("text", "")[:100] + "..." if metadata.get("text") else ""
             )
@@ -53,13 +92,13 @@ async def handle_read_resource(uri: AnyUrl) -> Union[str, bytes]:
 
     try:
         vector_id = str(uri).split("/")[-1]
-        record = pinecone_client.fetch_records([vector_id])
+        record = vector_store.fetch_records([vector_id])
 
-        if not record or "records" not in record or not record["records"]:
+        vector_data = record.get("vectors", {}).get(vector_id)
+        if not vector_data:
             raise ValueError(f"Vector not found: {vector_id}")
 
-        vector_data = record["records"][0]
-        metadata = vector_data.get("metadata", {})
+        metadata = vector_data.get("metadata") or {}
         content_type = metadata.get("content_type", "text/plain")
 
         if content_type.startswith("text/"):
@@ -79,24 +118,74 @@ def format_text_content(vector_data: dict) -> str:
     output.append(f"ID: {vector_data.get('id')}")
 
     for key, value in metadata.items():
-        if key not in ["title", "text", "content_type"]:
+        if key not in ["title", "text", "content_type", "text_ref"]:
             output.append(f"{key}: {value}")
 
     output.append("")
 
     if "text" in metadata:
-        output.append(metadata["text"])
+        output.append(resolve_text(blob_store, metadata))
 
     return "\n".join(output)
 
 
+def format_document(doc_id: str, chunks: list[dict]) -> str:
+    """
+    Format a chunked document as its shared metadata followed by the text
+    reassembled from its chunks.
+    """
+    chunks = sorted(chunks, key=lambda metadata: metadata.get("chunk_number", 0))
+    output = [f"Document ID: {doc_id}", f"Chunks: {len(chunks)}"]
+
+    total_chunks = chunks[-1].get("total_chunks")
+    if total_chunks and total_chunks != len(chunks):
+        output.append(
+            f"Warning: found {len(chunks)} of {total_chunks} chunks, "
+            "the text is incomplete"
+        )
+    output.append("")
+
+    metadata = document_metadata(chunks)
+    if metadata:
+        output.append("Metadata:")
+        for key, value in metadata.items():
+            output.append(f"{key}: {value}")
+        output.append("")
+
+    output.append("Content:")
+    output.append(
+        join_chunks([(resolve_text(blob_store, chunk), chunk) for chunk in chunks])
+    )
+    return "\n".join(output)
+
+
 def format_binary_content(vector_data: dict) -> bytes:
-    content = vector_data.get("metadata", {}).get("content", b"")
+    metadata = vector_data.get("metadata", {})
+    # Payloads live in the blob store, metadata only holds a reference
+    if metadata.get("content_ref"):
+        if blob_store is None:
+            raise ValueError("No blob store configured")
+        return blob_store.get(metadata["content_ref"])
+    content = metadata.get("content", b"")
     if isinstance(content, str):
         content = content.encode("utf-8")
     return content
 
 
+def format_namespaces(namespaces: dict) -> list[str]:
+    """
+    One line per namespace with its vector count, the default namespace first.
+    """
+    if not namespaces:
+        return ["- none"]
+    lines = []
+    for namespace, stats in sorted(namespaces.items()):
+        count = stats.get("vector_count", 0)
+        plural = "" if count == 1 else "s"
+        lines.append(f"- {namespace or '(default)'}: {count} vector{plural}")
+    return lines
+
+
 @server.list_tools()
 async def handle_list_tools() -> list[types.Tool]:
     return [
@@ -108,20 +197,52 @@ async def handle_list_tools() -> list[types.Tool]:
                 "type": "object",
                 "properties": {
                     "query": {"type": "string"},
-                    "top_k": {"type": "integer", "default": 10},
+                    "top_k": {"type": "integer", "default": server_config.top_k},
                     "namespace": {
                         "type": "string",
                         "description": "Optional namespace to search in",
                     },
-                    "category": {"type": "string"},
-                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "category": {
+                        "type": "string",
+                        "description": "Only return records with this category",
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Only return records with any of these tags",
+                    },
                     "date_range": {
                         "type": "object",
+                        "description": "Only return records whose date metadata "
+                        "falls in this range, both ends inclusive",
                         "properties": {
                             "start": {"type": "string", "format": "date"},
                             "end": {"type": "string", "format": "date"},
                         },
                     },
+                    "filter": {
+                        "type": "object",
+                        "description": "Optional Pinecone metadata filter, e.g. "
+                        '{"author": {"$eq": "Ada"}}, combined with the arguments '
+                        "above",
+                    },
+                    "alpha": {
+                        "type": "number",
+                        "minimum": 0,
+                        "maximum": 1,
+                        "description": "Weight of semantic against keyword matching "
+                        "when hybrid search is enabled, 1.0 is semantic only",
+                    },
+                    "rerank": {
+                        "type": "boolean",
+                        "default": server_config.rerank.enabled,
+                        "description": "Reorder candidates with a reranking model",
+                    },
+                    "rerank_top_n": {
+                        "type": "integer",
+                        "description": "Number of results to keep after reranking, "
+                        "defaults to top_k",
+                    },
                 },
                 "required": ["query"],
             },
@@ -142,6 +263,47 @@ async def handle_list_tools() -> list[types.Tool]:
                 "required": ["document_id"],
             },
         ),
+        types.Tool(
+            name="list-documents",
+            description="List the documents in the pinecone knowledge base",
+            category="read",
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "prefix": {
+                        "type": "string",
+                        "description": "Optional document ID prefix to filter by",
+                    },
+                    "namespace": {
+                        "type": "string",
+                        "description": "Optional namespace to list",
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "default": DEFAULT_LIST_LIMIT,
+                        "description": "Number of documents per page",
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "Cursor from the previous page to continue from",
+                    },
+                },
+            },
+        ),
+        types.Tool(
+            name="describe-index",
+            description="Describe the pinecone index: its dimension, metric, host, "
+            "vector counts and the embedding model in use",
+            category="read",
+            inputSchema={"type": "object", "properties": {}},
+        ),
+        types.Tool(
+            name="list-namespaces",
+            description="List the namespaces of the pinecone index with their "
+            "vector counts",
+            category="read",
+            inputSchema={"type": "object", "properties": {}},
+        ),
         types.Tool(
             name="upsert-document",
             description="Add or update content in the pinecone knowledge base",
@@ -152,14 +314,76 @@ async def handle_list_tools() -> list[types.Tool]:
                     "id": {"type": "string"},
                     "text": {"type": "string"},
                     "metadata": {"type": "object"},
+                    "content": {
+                        "type": "string",
+                        "contentEncoding": "base64",
+                        "description": "Optional base64 binary payload such as an "
+                        "attachment, set metadata.content_type to its MIME type. "
+                        "text should describe or transcribe it for search",
+                    },
                     "namespace": {
                         "type": "string",
                         "description": "Optional namespace to store the document in",
                     },
+                    "chunking": {
+                        "description": "Chunking strategy, by name or with parameters. "
+                        "Defaults to the namespace's configured strategy",
+                        "oneOf": [
+                            {"type": "string", "enum": list(CHUNKING_STRATEGIES)},
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "strategy": {
+                                        "type": "string",
+                                        "enum": list(CHUNKING_STRATEGIES),
+                                    },
+                                    "headers": {
+                                        "type": "array",
+                                        "items": {"type": "string"},
+                                    },
+                                    "max_tokens": {"type": "integer"},
+                                    "min_tokens": {"type": "integer"},
+                                    "overlap_tokens": {"type": "integer"},
+                                    "language": {"type": "string"},
+                                    "breadcrumbs": {
+                                        "type": "boolean",
+                                        "description": "Embed each chunk after "
+                                        "its document title and header path",
+                                    },
+                                },
+                            },
+                        ],
+                    },
                 },
                 "required": ["id", "text"],
             },
         ),
+        types.Tool(
+            name="delete-document",
+            description="Delete a document and all of its chunks from the pinecone "
+            "knowledge base",
+            category="mutation",
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "The document ID it was upserted with",
+                    },
+                    "namespace": {
+                        "type": "string",
+                        "description": "Optional namespace to delete from",
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "default": False,
+                        "description": "List the chunks that would be deleted "
+                        "without deleting them",
+                    },
+                },
+                "required": ["id"],
+            },
+        ),
     ]
 
 
@@ -170,27 +394,52 @@ async def handle_call_tool(
     try:
         if name == "semantic-search":
             query = arguments.get("query")
-            top_k = arguments.get("top_k", 10)
-            filters = arguments.get("filters")
+            top_k = arguments.get("top_k", server_config.top_k)
+            filters = build_search_filter(
+                category=arguments.get("category"),
+                tags=arguments.get("tags"),
+                date_range=arguments.get("date_range"),
+                filter=arguments.get("filter"),
+            )
             namespace = arguments.get("namespace")
+            rerank = arguments.get("rerank", server_config.rerank.enabled)
+            rerank_top_n = arguments.get("rerank_top_n", top_k)
+
+            # Without a sparse encoder the search is dense only
+            alpha = None
+            if server_config.sparse.encoder:
+                alpha = arguments.get("alpha", server_config.sparse.alpha)
 
-            results = pinecone_client.search_records(
+            results = vector_store.search_records(
                 query=query,
-                top_k=top_k,
+                # Over-fetch so the reranker has candidates to promote
+                top_k=(
+                    max(top_k, rerank_top_n, server_config.rerank.candidates)
+                    if rerank
+                    else top_k
+                ),
                 filter=filters,
                 include_metadata=True,
                 namespace=namespace,
+                alpha=alpha,
             )
 
             matches = results.get("matches", [])
+            if rerank:
+                matches = rerank_matches(get_reranker(), query, matches, rerank_top_n)
 
             # Format results with rich context
             formatted_text = "Retrieved Contexts:\n\n"
             for i, match in enumerate(matches, 1):
                 metadata = match.get("metadata", {})
-                formatted_text += f"[Result {i} - Similarity: {match['score']:.3f}]\n"
+                scores = f"Similarity: {match['score']:.3f}"
+                if "rerank_score" in match:
+                    scores += f" - Rerank: {match['rerank_score']:.3f}"
+                formatted_text += f"[Result {i} - {scores}]\n"
                 formatted_text += f"Document ID: {match['id']}\n"
                 formatted_text += f"{metadata.get('text', '').strip()}\n"
+                if metadata.get("text_ref"):
+                    formatted_text += "[Preview, read-document returns the full text]\n"
                 formatted_text += "-" * 40 + "\n\n"
 
             return [types.TextContent(type="text", text=formatted_text)]
@@ -202,15 +451,25 @@ async def handle_call_tool(
                 raise ValueError("document_id is required")
 
             # Fetch the record using your existing fetch_records method
-            record = pinecone_client.fetch_records([document_id], namespace=namespace)
+            record = vector_store.fetch_records([document_id], namespace=namespace)
 
             # Get the vector data for this document
-            vector = record.vectors.get(document_id)
+            vector = record.get("vectors", {}).get(document_id)
             if not vector:
-                raise ValueError(f"Document {document_id} not found")
+                # Upserted documents are stored as {document_id}#chunk{n}
+                chunk_ids = list_chunk_ids(
+                    vector_store, document_id, namespace=namespace
+                )
+                chunks = fetch_metadata(vector_store, chunk_ids, namespace=namespace)
+                if not chunks:
+                    raise ValueError(f"Document {document_id} not found")
+                text = format_document(document_id, list(chunks.values()))
+                return [types.TextContent(type="text", text=text)]
 
-            # Get metadata from the vector
-            metadata = vector.metadata if hasattr(vector, "metadata") else {}
+            # Get metadata from the vector, with the full text if it's a blob
+            metadata = vector.get("metadata") or {}
+            if "text" in metadata:
+                metadata = {**metadata, "text": resolve_text(blob_store, metadata)}
 
             # Format the document content
             formatted_content = []
@@ -224,35 +483,200 @@ async def handle_call_tool(
 
             return [types.TextContent(type="text", text="\n".join(formatted_content))]
 
+        elif name == "list-documents":
+            limit = arguments.get("limit", DEFAULT_LIST_LIMIT)
+            if limit < 1:
+                raise ValueError(f"limit must be positive, got: {limit}")
+
+            listing = list_documents(
+                vector_store,
+                prefix=arguments.get("prefix"),
+                namespace=arguments.get("namespace"),
+                limit=limit,
+                cursor=arguments.get("cursor"),
+            )
+
+            documents = listing["documents"]
+            if not documents:
+                return [types.TextContent(type="text", text="No documents found")]
+
+            formatted_text = f"Documents ({len(documents)}):\n\n"
+            for document in documents:
+                chunks = document["chunks"]
+                plural = "" if chunks == 1 else "s"
+                formatted_text += f"- {document['id']} ({chunks} chunk{plural})"
+                if document["title"]:
+                    formatted_text += f": {document['title']}"
+                formatted_text += "\n"
+            if listing["cursor"]:
+                formatted_text += (
+                    f"\nMore documents follow, pass cursor: {listing['cursor']}\n"
+                )
+
+            return [types.TextContent(type="text", text=formatted_text)]
+
+        elif name == "describe-index":
+            description = vector_store.describe_index()
+            dimension = description.get("dimension")
+            provider = server_config.embedder_provider
+
+            lines = [
+                f"Index: {description.get('name')}",
+                f"Backend: {server_config.backend}",
+                f"Host: {description.get('host') or 'n/a'}",
+                f"Dimension: {dimension}",
+                f"Metric: {description.get('metric')}",
+                f"Total vectors: {description.get('total_vector_count', 0)}",
+                f"Fullness: {description.get('index_fullness', 0.0):.1%}",
+                f"Embedder: {provider}, "
+                f"{server_config.pinecone.dimension} dimensions",
+            ]
+            # The hashing embedder has no model
+            if provider != "hashing":
+                lines.append(f"Embedding model: {server_config.pinecone.model}")
+            if dimension and dimension != server_config.pinecone.dimension:
+                lines.append(
+                    f"Warning: the embedding model produces "
+                    f"{server_config.pinecone.dimension}-dimensional vectors but the "
+                    f"index stores {dimension}-dimensional ones"
+                )
+            lines.append("")
+            lines.append("Namespaces:")
+            lines.extend(format_namespaces(description.get("namespaces", {})))
+
+            return [types.TextContent(type="text", text="\n".join(lines))]
+
+        elif name == "list-namespaces":
+            namespaces = vector_store.describe_index().get("namespaces", {})
+            lines = [f"Namespaces ({len(namespaces)}):"]
+            lines.extend(format_namespaces(namespaces))
+            return [types.TextContent(type="text", text="\n".join(lines))]
+
         elif name == "upsert-document":
             doc_id = arguments.get("id")
             text = arguments.get("text")
-            metadata = arguments.get("metadata", {})
+            # Front-matter applies to every chunk, explicit metadata wins over it
+            front_matter, text = extract_front_matter(text)
+
+            # Fail on metadata Pinecone would reject before spending on embeddings
+            metadata = normalize_metadata(
+                {**front_matter, **(arguments.get("metadata") or {})}
+            )
             namespace = arguments.get("namespace")
 
-            chunker = MarkdownChunker()
+            # Binary payloads are stored once and referenced from every chunk
+            content = arguments.get("content")
+            if content:
+                try:
+                    payload = base64.b64decode(content, validate=True)
+                except binascii.Error as e:
+                    raise ValueError(f"content must be base64 encoded: {e}")
+                metadata["content_ref"] = blob_store.put(payload)
+                metadata["content_size"] = len(payload)
+                metadata.setdefault("content_type", "application/octet-stream")
+
+            chunker = create_chunker(
+                server_config, namespace, arguments.get("chunking")
+            )
             chunks = chunker.chunk_document(doc_id, text, metadata)
             # count chunks
             logger.info(f"Chunk count: {len(chunks)}")
-            records = []
             for chunk in chunks:
-                # Create an embedding for each chunk
-                embedding = pinecone_client.generate_embeddings(chunk.content)
+                chunk.metadata["content_hash"] = chunk.content_hash
 
-                # Use text directly - Pinecone will generate the embedding
+            # Compare with the chunks stored by a previous upsert of the document
+            existing_ids = list_chunk_ids(vector_store, doc_id, namespace=namespace)
+            existing = fetch_metadata(
+                vector_store,
+                [chunk.id for chunk in chunks if chunk.id in existing_ids],
+                namespace=namespace,
+            )
+            changed = []
+            moved = []
+            for chunk in chunks:
+                stored = existing.get(chunk.id, {})
+                if stored.get("content_hash") != chunk.metadata["content_hash"]:
+                    changed.append(chunk)
+                elif any(
+                    stored.get(key) != chunk.metadata[key]
+                    for key in POSITION_METADATA_KEYS
+                ):
+                    moved.append(chunk)
+
+            # Embed changed chunks together, batched within the API limits.
+            # Breadcrumbs are only embedded, records keep the original text
+            embeddings = []
+            if changed:
+                embeddings = vector_store.generate_embeddings_batch(
+                    [chunk.embedding_text for chunk in changed]
+                )
+            records = []
+            for chunk, embedding in zip(changed, embeddings):
                 record = PineconeRecord(
                     id=chunk.id,
                     embedding=embedding,
                     text=chunk.content,
-                    metadata={**metadata},
+                    metadata=chunk.metadata,
                 )
                 records.append(record)
 
-            pinecone_client.upsert_records(records, namespace=namespace)
+            if records:
+                vector_store.upsert_records(records, namespace=namespace)
+
+            # Chunks that only moved, such as when the chunk count changes,
+            # keep their embedding
+            for chunk in moved:
+                vector_store.update_metadata(
+                    chunk.id,
+                    {key: chunk.metadata[key] for key in POSITION_METADATA_KEYS},
+                    namespace=namespace,
+                )
+
+            # Drop chunks the document no longer has, after the new ones are in
+            chunk_ids = {chunk.id for chunk in chunks}
+            removed = delete_record_ids(
+                vector_store,
+                [record_id for record_id in existing_ids if record_id not in chunk_ids],
+                namespace=namespace,
+            )
 
+            added = sum(1 for chunk in changed if chunk.id not in existing_ids)
+            updated = len(changed) - added
+            unchanged = len(chunks) - len(changed)
+            return [
+                types.TextContent(
+                    type="text",
+                    text=f"Successfully upserted document: {doc_id} "
+                    f"({added} added, {updated} updated, {unchanged} unchanged, "
+                    f"{removed} removed)",
+                )
+            ]
+        elif name == "delete-document":
+            doc_id = arguments.get("id")
+            namespace = arguments.get("namespace")
+            dry_run = arguments.get("dry_run", False)
+            if not doc_id:
+                raise ValueError("id is required")
+
+            # Chunk IDs are {doc_id}#chunk{n}
+            chunk_ids = list_chunk_ids(vector_store, doc_id, namespace=namespace)
+            if not chunk_ids:
+                return [
+                    types.TextContent(
+                        type="text", text=f"No chunks found for document: {doc_id}"
+                    )
+                ]
+
+            if dry_run:
+                lines = [f"Would delete {len(chunk_ids)} chunks of document {doc_id}:"]
+                lines.extend(chunk_ids)
+                return [types.TextContent(type="text", text="\n".join(lines))]
+
+            removed = delete_record_ids(vector_store, chunk_ids, namespace=namespace)
             return [
                 types.TextContent(
-                    type="text", text=f"Successfully upserted document: {doc_id}"
+                    type="text",
+                    text=f"Successfully deleted document: {doc_id} ({removed} chunks)",
                 )
             ]
         else:
@@ -323,11 +747,17 @@ async def handle_list_prompts() -> list[types.Prompt]:
     ]
 
 
-async def main():
-    logger.info("Starting Pinecone MCP server")
+async def main(config: ServerConfig):
+    logger.info(f"Starting Pinecone MCP server with {config.backend} backend")
 
-    global pinecone_client
-    pinecone_client = PineconeClient()
+    global vector_store, blob_store, server_config
+    server_config = config
+    blob_store = BlobStore(
+        os.path.expanduser(config.blobs.path),
+        inline_max_bytes=config.blobs.inline_max_bytes,
+        preview_chars=config.blobs.preview_chars,
+    )
+    vector_store = create_vector_store(config, blob_store=blob_store)
 
     async with mcp.server.stdio.stdio_server() as (read_stream, write_stream):
         await server.run(
@@ -0,0 +1,248 @@
+import hashlib
+import logging
+import math
+import re
+from collections import Counter
+from typing import Any, Dict, List, Optional, Protocol
+
+from .config import ServerConfig
+from .constants import (
+    DEFAULT_MODEL_LIMITS,
+    INFERENCE_MODEL_LIMITS,
+    SPARSE_ENCODERS,
+)
+from .embeddings import batch_texts
+
+logger = logging.getLogger(__name__)
+
+# Keeps product codes and error strings such as "E-1042" or "v2.3.1" whole
+SPARSE_TOKEN_PATTERN = re.compile(r"\w+(?:[-_.:/]\w+)*", re.UNICODE)
+
+# BM25 saturation and length normalization parameters
+BM25_K1 = 1.2
+BM25_B = 0.75
+
+# Assumed average document length in tokens, there are no corpus statistics
+BM25_AVG_DOC_LENGTH = 200
+
+# Sparse indices must fit in an unsigned 32 bit integer
+SPARSE_INDEX_SPACE = 2**32
+
+# A sparse vector, {"indices": [...], "values": [...]} as Pinecone expects
+SparseVector = Dict[str, List]
+
+
+class SparseEncoder(Protocol):
+    """
+    Turns text into sparse vectors for keyword-style matching.
+    """
+
+    def encode_query(self, text: str) -> SparseVector: ...
+
+    def encode_documents(self, texts: List[str]) -> List[SparseVector]: ...
+
+
+def sparse_dot(a: SparseVector, b: SparseVector) -> float:
+    """
+    Compute the dot product of two sparse vectors.
+
+    Parameters:
+        a: The first sparse vector.
+        b: The second sparse vector.
+
+    Returns:
+        float: The dot product.
+    """
+    weights = dict(zip(a["indices"], a["values"]))
+    return sum(weights.get(i, 0.0) * v for i, v in zip(b["indices"], b["values"]))
+
+
+def hybrid_score(
+    dense_score: float,
+    sparse_query: Optional[SparseVector],
+    sparse_values: Optional[SparseVector],
+    alpha: float,
+) -> float:
+    """
+    Combine dense and sparse similarity the way a Pinecone hybrid query does.
+
+    Parameters:
+        dense_score: Similarity of the dense vectors.
+        sparse_query: The sparse query vector, None for dense-only search.
+        sparse_values: The record's sparse vector, if it has one.
+        alpha: Weight of the dense score, the sparse score gets 1 - alpha.
+
+    Returns:
+        float: The combined score.
+    """
+    if sparse_query is None:
+        return dense_score
+    sparse_score = sparse_dot(sparse_query, sparse_values) if sparse_values else 0.0
+    return alpha * dense_score + (1 - alpha) * sparse_score
+
+
+def scale_sparse(vector: SparseVector, factor: float) -> SparseVector:
+    return {
+        "indices": list(vector["indices"]),
+        "values": [v * factor for v in vector["values"]],
+    }
+
+
+def encode_records(
+    encoder: Optional[SparseEncoder], records: List[Any]
+) -> List[Optional[SparseVector]]:
+    """
+    Sparse vectors for records about to be upserted, encoding any that lack one.
+
+    Parameters:
+        encoder: The sparse encoder, None when hybrid search is off.
+        records: The PineconeRecords being upserted.
+
+    Returns:
+        List[Optional[SparseVector]]: One per record, None where there is none.
+    """
+    vectors = [record.sparse_values for record in records]
+    missing = [i for i, vector in enumerate(vectors) if vector is None]
+    if encoder and missing:
+        encoded = encoder.encode_documents([records[i].text for i in missing])
+        for i, vector in zip(missing, encoded):
+            vectors[i] = vector
+    # Pinecone rejects empty sparse vectors
+    return [vector if vector and vector["indices"] else None for vector in vectors]
+
+
+class BM25Encoder:
+    """
+    A local BM25-style encoder. Terms are hashed into indices so no
+    vocabulary is needed. Documents get saturated term frequencies,
+    queries weigh each distinct term equally.
+    """
+
+    def tokenize(self, text: str) -> List[str]:
+        return SPARSE_TOKEN_PATTERN.findall(text.lower())
+
+    def index(self, token: str) -> int:
+        digest = hashlib.blake2b(token.encode("utf-8"), digest_size=4).digest()
+        return int.from_bytes(digest, "little") % SPARSE_INDEX_SPACE
+
+    def encode_query(self, text: str) -> SparseVector:
+        """
+        Encode a search query.
+
+        Parameters:
+            text: The query to encode.
+
+        Returns:
+            SparseVector: The normalized sparse vector.
+        """
+        return self._normalize({self.index(t): 1.0 for t in set(self.tokenize(text))})
+
+    def encode_documents(self, texts: List[str]) -> List[SparseVector]:
+        """
+        Encode documents with BM25 term frequency saturation.
+
+        Parameters:
+            texts: The documents to encode.
+
+        Returns:
+            List[SparseVector]: The normalized sparse vectors, in order.
+        """
+        vectors = []
+        for text in texts:
+            tokens = self.tokenize(text)
+            length_norm = 1 - BM25_B + BM25_B * len(tokens) / BM25_AVG_DOC_LENGTH
+            weights: Dict[int, float] = {}
+            for token, tf in Counter(tokens).items():
+                index = self.index(token)
+                score = tf * (BM25_K1 + 1) / (tf + BM25_K1 * length_norm)
+                weights[index] = weights.get(index, 0.0) + score
+            vectors.append(self._normalize(weights))
+        return vectors
+
+    def _normalize(self, weights: Dict[int, float]) -> SparseVector:
+        norm = math.sqrt(sum(v * v for v in weights.values()))
+        indices = sorted(weights)
+        return {
+            "indices": indices,
+            "values": [weights[i] / norm for i in indices] if norm else [],
+        }
+
+
+class PineconeSparseEncoder:
+    """
+    Encodes text with a Pinecone hosted sparse model such as
+    pinecone-sparse-english-v0.
+    """
+
+    def __init__(self, config: ServerConfig):
+        from .pinecone import pinecone_client
+
+        self.model = config.sparse.model
+        self.pc = pinecone_client(config.pinecone)
+
+    def encode_query(self, text: str) -> SparseVector:
+        """
+        Encode a search query with the Pinecone Inference API.
+
+        Parameters:
+            text: The query to encode.
+
+        Returns:
+            SparseVector: The sparse vector.
+        """
+        return self._encode([text], "query")[0]
+
+    def encode_documents(self, texts: List[str]) -> List[SparseVector]:
+        """
+        Encode documents with the Pinecone Inference API.
+
+        Parameters:
+            texts: The documents to encode.
+
+        Returns:
+            List[SparseVector]: The sparse vectors, in order.
+        """
+        limits = INFERENCE_MODEL_LIMITS.get(self.model, DEFAULT_MODEL_LIMITS)
+        vectors = []
+        for batch in batch_texts(
+            texts, limits["max_inputs"], limits["max_tokens_per_input"]
+        ):
+            vectors.extend(self._encode(batch, "passage"))
+        return vectors
+
+    def _encode(self, texts: List[str], input_type: str) -> List[SparseVector]:
+        response = self.pc.inference.embed(
+            model=self.model,
+            inputs=texts,
+            parameters={"input_type": input_type, "truncate": "END"},
+        )
+        if not response.data or len(response.data) != len(texts):
+            raise ValueError(
+                f"Failed to generate sparse embeddings for texts: {texts}"
+            )
+        return [
+            {"indices": list(item.sparse_indices), "values": list(item.sparse_values)}
+            for item in response.data
+        ]
+
+
+def create_sparse_encoder(config: ServerConfig) -> Optional[SparseEncoder]:
+    """
+    Create the configured sparse encoder, if hybrid search is enabled.
+
+    Parameters:
+        config: The server configuration.
+
+    Returns:
+        Optional[SparseEncoder]: The encoder, None for dense-only search.
+    """
+    encoder = config.sparse.encoder
+    if not encoder:
+        return None
+    if encoder == "bm25":
+        return BM25Encoder()
+    if encoder == "pinecone":
+        return PineconeSparseEncoder(config)
+    raise ValueError(
+        f"Unknown sparse encoder: {encoder}. Expected one of: {', '.join(SPARSE_ENCODERS)}"
+    )
@@ -0,0 +1,382 @@
+import json
+import logging
+import os
+import sqlite3
+from array import array
+from typing import Any, Dict, List, Optional, Union
+
+from .constants import INFERENCE_DIMENSION
+from .embeddings import Embedder, HashingEmbedder
+from .filters import matches_filter
+from .memory_store import cosine_similarity
+from .pinecone import PineconeRecord
+from .sparse import SparseEncoder, encode_records, hybrid_score
+
+logger = logging.getLogger(__name__)
+
+SCHEMA = """
+CREATE TABLE IF NOT EXISTS store_info (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS records (
+    namespace TEXT NOT NULL,
+    id TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    text TEXT NOT NULL,
+    metadata TEXT NOT NULL,
+    sparse TEXT,
+    PRIMARY KEY (namespace, id)
+);
+"""
+
+
+def _pack(values: List[float]) -> bytes:
+    return array("f", values).tobytes()
+
+
+def _unpack(blob: bytes) -> List[float]:
+    values = array("f")
+    values.frombytes(blob)
+    return values.tolist()
+
+
+class SQLiteVectorStore:
+    """
+    A vector store persisted to a local SQLite file.
+    Search is a brute force cosine scan, fine for a personal knowledge base.
+    """
+
+    def __init__(
+        self,
+        path: str,
+        dimension: int = INFERENCE_DIMENSION,
+        embedder: Optional[Embedder] = None,
+        sparse_encoder: Optional[SparseEncoder] = None,
+    ):
+        self.path = path
+        self.dimension = dimension
+        self.embedder = embedder or HashingEmbedder(dimension)
+        self.sparse_encoder = sparse_encoder
+
+        directory = os.path.dirname(path)
+        if directory:
+            os.makedirs(directory, exist_ok=True)
+
+        self.conn = sqlite3.connect(path, check_same_thread=False)
+        self.conn.executescript(SCHEMA)
+        self.migrate()
+        self.ensure_dimension()
+
+    def migrate(self):
+        """
+        Bring databases created by older versions up to the current schema.
+        """
+        columns = {row[1] for row in self.conn.execute("PRAGMA table_info(records)")}
+        if "sparse" not in columns:
+            with self.conn:
+                self.conn.execute("ALTER TABLE records ADD COLUMN sparse TEXT")
+
+    def ensure_dimension(self):
+        """
+        Record the store dimension on first use and refuse to open it with another.
+        """
+        row = self.conn.execute(
+            "SELECT value FROM store_info WHERE key = 'dimension'"
+        ).fetchone()
+        if row is None:
+            with self.conn:
+                self.conn.execute(
+                    "INSERT INTO store_info (key, value) VALUES ('dimension', ?)",
+                    (str(self.dimension),),
+                )
+        elif int(row[0]) != self.dimension:
+            raise ValueError(
+                f"Database {self.path} stores {row[0]}-dimensional vectors, "
+                f"but {self.dimension} were requested"
+            )
+
+    def generate_embeddings(self, text: str) -> List[float]:
+        """
+        Generate passage embeddings for a given text with the configured embedder.
+
+        Parameters:
+            text: The text to generate embeddings for.
+
+        Returns:
+            List[float]: The embeddings for the text.
+        """
+        return self.embedder.embed_passages([text])[0]
+
+    def generate_embeddings_batch(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate passage embeddings for many texts in as few calls as possible.
+
+        Parameters:
+            texts: The texts to generate embeddings for.
+
+        Returns:
+            List[List[float]]: The embeddings, in the same order as texts.
+        """
+        return self.embedder.embed_passages(texts)
+
+    def upsert_records(
+        self,
+        records: List[PineconeRecord],
+        namespace: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        Upsert records into the SQLite database.
+
+        Parameters:
+            records: List of records to upsert.
+            namespace: Optional namespace to upsert into.
+
+        Returns:
+            Dict[str, Any]: The number of records upserted.
+        """
+        rows = []
+        sparse_vectors = encode_records(self.sparse_encoder, records)
+        for record, sparse_values in zip(records, sparse_vectors):
+            # Don't continue if there's no vector values
+            if not record.embedding:
+                continue
+
+            if len(record.embedding) != self.dimension:
+                raise ValueError(
+                    f"Vector dimension {len(record.embedding)} does not match "
+                    f"store dimension {self.dimension}"
+                )
+
+            rows.append(
+                (
+                    namespace or "",
+                    record.id,
+                    _pack(record.embedding),
+                    record.text,
+                    json.dumps(record.metadata),
+                    json.dumps(sparse_values) if sparse_values else None,
+                )
+            )
+
+        try:
+            with self.conn:
+                self.conn.executemany(
+                    "INSERT OR REPLACE INTO records "
+                    "(namespace, id, embedding, text, metadata, sparse) "
+                    "VALUES (?, ?, ?, ?, ?, ?)",
+                    rows,
+                )
+        except sqlite3.Error as e:
+            logger.error(f"Error upserting records: {e}")
+            raise
+
+        return {"upserted_count": len(rows)}
+
+    def search_records(
+        self,
+        query: Union[str, List[float]],
+        top_k: int = 10,
+        namespace: Optional[str] = None,
+        filter: Optional[Dict] = None,
+        include_metadata: bool = True,
+        alpha: Optional[float] = None,
+    ) -> Dict[str, Any]:
+        """
+        Search records by cosine similarity.
+
+        Parameters:
+            query: The query to search for.
+            top_k: The number of results to return.
+            namespace: Optional namespace to search in.
+            filter: Optional filter to apply to the search.
+            include_metadata: Whether to include metadata in the search results.
+            alpha: Optional weight of dense against sparse scores for hybrid
+                search, 1.0 is dense only. Needs a sparse encoder and a text query.
+
+        Returns:
+            Dict[str, Any]: The matches, best first.
+        """
+        if isinstance(query, str):
+            vector = self.embedder.embed_query(query)
+        else:
+            vector = query
+
+        sparse_query = None
+        if alpha is not None and self.sparse_encoder and isinstance(query, str):
+            sparse_query = self.sparse_encoder.encode_query(query)
+
+        rows = self.conn.execute(
+            "SELECT id, embedding, text, metadata, sparse FROM records "
+            "WHERE namespace = ?",
+            (namespace or "",),
+        )
+
+        matches = []
+        for record_id, embedding, text, metadata_json, sparse_json in rows:
+            metadata = {**json.loads(metadata_json), "text": text}
+            if not matches_filter(metadata, filter):
+                continue
+            score = hybrid_score(
+                cosine_similarity(vector, _unpack(embedding)),
+                sparse_query,
+                json.loads(sparse_json) if sparse_json else None,
+                alpha,
+            )
+            match = {"id": record_id, "score": score}
+            if include_metadata:
+                match["metadata"] = metadata
+            matches.append(match)
+
+        matches.sort(key=lambda m: m["score"], reverse=True)
+        return {"matches": matches[:top_k], "namespace": namespace or ""}
+
+    def delete_records(
+        self, ids: List[str], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Delete records by ID
+
+        Parameters:
+            ids: List of record IDs to delete
+            namespace: Optional namespace to delete from
+        """
+        try:
+            with self.conn:
+                self.conn.executemany(
+                    "DELETE FROM records WHERE namespace = ? AND id = ?",
+                    [(namespace or "", record_id) for record_id in ids],
+                )
+            return {}
+        except sqlite3.Error as e:
+            logger.error(f"Error deleting records: {e}")
+            raise
+
+    def update_metadata(
+        self, record_id: str, metadata: Dict[str, Any], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Set metadata fields of a record, keeping its vector and other fields
+
+        Parameters:
+            record_id: ID of the record to update
+            metadata: The fields to set
+            namespace: Optional namespace of the record
+        """
+        try:
+            with self.conn:
+                row = self.conn.execute(
+                    "SELECT metadata FROM records WHERE namespace = ? AND id = ?",
+                    (namespace or "", record_id),
+                ).fetchone()
+                if row is not None:
+                    self.conn.execute(
+                        "UPDATE records SET metadata = ? WHERE namespace = ? AND id = ?",
+                        (
+                            json.dumps({**json.loads(row[0]), **metadata}),
+                            namespace or "",
+                            record_id,
+                        ),
+                    )
+            return {}
+        except sqlite3.Error as e:
+            logger.error(f"Error updating metadata: {e}")
+            raise
+
+    def fetch_records(
+        self, ids: List[str], namespace: Optional[str] = None
+    ) -> Dict[str, Any]:
+        """
+        Fetch specific records by ID
+
+        Parameters:
+            ids: List of record IDs to fetch
+            namespace: Optional namespace to fetch from
+
+        Returns:
+            Dict[str, Any]: The records found, keyed by ID.
+        """
+        vectors = {}
+        for record_id in ids:
+            row = self.conn.execute(
+                "SELECT embedding, text, metadata FROM records WHERE namespace = ? AND id = ?",
+                (namespace or "", record_id),
+            ).fetchone()
+            if row is None:
+                continue
+            embedding, text, metadata_json = row
+            vectors[record_id] = {
+                "id": record_id,
+                "values": _unpack(embedding),
+                "metadata": {**json.loads(metadata_json), "text": text},
+            }
+
+        return {"vectors": vectors, "namespace": namespace or ""}
+
+    def describe_index(self) -> Dict[str, Any]:
+        """
+        Describe the database and count its vectors per namespace.
+
+        Returns:
+            Dict[str, Any]: The name, dimension, metric, host, total_vector_count,
+            index_fullness and namespaces with their vector_count.
+        """
+        rows = self.conn.execute(
+            "SELECT namespace, COUNT(*) FROM records GROUP BY namespace"
+        )
+        namespaces = {namespace: {"vector_count": count} for namespace, count in rows}
+        return {
+            "name": self.path,
+            "dimension": self.dimension,
+            "metric": "cosine",
+            "host": None,
+            "total_vector_count": sum(
+                namespace["vector_count"] for namespace in namespaces.values()
+            ),
+            # Disk space is the only limit
+            "index_fullness": 0.0,
+            "namespaces": namespaces,
+        }
+
+    def list_records(
+        self,
+        prefix: Optional[str] = None,
+        limit: int = 100,
+        namespace: Optional[str] = None,
+        pagination_token: Optional[str] = None,
+    ) -> Dict[str, Any]:
+        """
+        List records in the database, ordered by ID.
+
+        Parameters:
+            prefix: Optional prefix to filter records by.
+            limit: The number of records to return per page.
+            namespace: Optional namespace to list records from.
+            pagination_token: Optional token of the previous page to continue from.
+        """
+        # The token is the last ID of the previous page, one extra row
+        # tells whether another page follows
+        rows = self.conn.execute(
+            "SELECT id, text, metadata FROM records "
+            "WHERE namespace = ? AND substr(id, 1, ?) = ? AND id > ? "
+            "ORDER BY id LIMIT ?",
+            (
+                namespace or "",
+                len(prefix or ""),
+                prefix or "",
+                pagination_token or "",
+                limit + 1,
+            ),
+        ).fetchall()
+        page = rows[:limit]
+        return {
+            "vectors": [
+                {
+                    "id": record_id,
+                    "metadata": {**json.loads(metadata_json), "text": text},
+                }
+                for record_id, text, metadata_json in page
+            ],
+            "namespace": namespace or "",
+            "pagination_token": page[-1][0] if len(rows) > limit else None,
+        }
@@ -1,3 +1,20 @@
+import math
+import re
+
+# Scripts written without spaces between words: Thai, Lao, Myanmar, Khmer,
+# Hiragana, Katakana, CJK ideographs and Hangul
+UNSPACED_SCRIPTS = (
+    "\u0e00-\u0eff\u1000-\u109f\u1780-\u17ff\u3040-\u30ff"
+    "\u3400-\u4dbf\u4e00-\u9fff\uac00-\ud7af\uf900-\ufaff\U00020000-\U0002fa1f"
+)
+
+# Words, punctuation and single characters of unspaced scripts, a rough
+# stand-in for subword tokens
+TOKEN_PATTERN = re.compile(
+    rf"[{UNSPACED_SCRIPTS}]|[^\W{UNSPACED_SCRIPTS}]+|[^\w\s]", re.UNICODE
+)
+
+
 class MCPToolError(Exception):
     """Custom exception for MCP tool errors"""
 
@@ -24,3 +41,19 @@ def is_valid_vector_uri(uri: str) -> bool:
         return bool(vector_id.strip())  # Ensure non-empty ID
     except Exception:
         return False
+
+
+def estimate_tokens(text: str) -> int:
+    """
+    Estimate how many model tokens a text uses without loading a tokenizer.
+    Subword tokenizers split words into about 1.3 pieces on average,
+    so this errs on the high side. Characters of scripts written without
+    spaces, such as Chinese or Thai, count as a word each.
+
+    Parameters:
+        text: The text to measure.
+
+    Returns:
+        int: The estimated token count.
+    """
+    return math.ceil(len(TOKEN_PATTERN.findall(text)) * 1.3)
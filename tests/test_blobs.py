@@ -0,0 +1,65 @@
+import os
+import tempfile
+import unittest
+
+from mcp_pinecone.blobs import BlobStore, resolve_text
+from mcp_pinecone.metadata import MetadataError, fit_metadata, metadata_size
+
+
+class BlobTestCase(unittest.TestCase):
+    def setUp(self):
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        self.blobs = BlobStore(directory.name, inline_max_bytes=100, preview_chars=10)
+
+
+class BlobStoreTest(BlobTestCase):
+    def test_content_addressed(self):
+        ref = self.blobs.put(b"\x89PNG")
+        self.assertRegex(ref, r"^sha256:[0-9a-f]{64}$")
+        self.assertEqual(self.blobs.put(b"\x89PNG"), ref)
+        self.assertEqual(self.blobs.get(ref), b"\x89PNG")
+        self.assertEqual(os.listdir(self.blobs.path), [ref[7:9]])
+
+    def test_invalid_and_missing_refs(self):
+        with self.assertRaisesRegex(ValueError, "Invalid blob reference"):
+            self.blobs.get("sha256:../../etc/passwd")
+        with self.assertRaisesRegex(ValueError, "Blob not found"):
+            self.blobs.get("sha256:" + "0" * 64)
+
+    def test_resolve_text(self):
+        ref = self.blobs.put_text("The full text")
+        metadata = {"text": "The full", "text_ref": ref}
+        self.assertEqual(resolve_text(self.blobs, metadata), "The full text")
+        self.assertEqual(resolve_text(None, metadata), "The full")
+        self.assertEqual(resolve_text(self.blobs, {"text": "Inline"}), "Inline")
+
+        missing = {"text": "Preview", "text_ref": "sha256:" + "0" * 64}
+        self.assertEqual(resolve_text(self.blobs, missing), "Preview")
+
+
+class FitMetadataTest(BlobTestCase):
+    def test_small_text_stays_inline(self):
+        fitted = fit_metadata("a", {"tags": [1, True]}, "Short", blob_store=self.blobs)
+        self.assertEqual(fitted, {"tags": ["1", "true"], "text": "Short"})
+
+    def test_large_text_moves_to_the_blob_store(self):
+        text = "Refunds are issued within 30 days. " * 10
+        fitted = fit_metadata("a", {}, text, blob_store=self.blobs)
+        self.assertEqual(fitted["text"], text[:10])
+        self.assertEqual(fitted["text_size"], len(text))
+        self.assertEqual(resolve_text(self.blobs, fitted), text)
+
+    def test_text_is_truncated_to_the_limit(self):
+        text = "Café \"crème\" ☕ " * 20
+        fitted = fit_metadata("a", {"category": "menu"}, text, max_bytes=120)
+        self.assertLessEqual(metadata_size(fitted), 120)
+        self.assertTrue(fitted["text"])
+        self.assertTrue(text.startswith(fitted["text"]))
+        self.assertEqual(fitted["category"], "menu")
+
+    def test_metadata_over_the_limit_names_the_largest_field(self):
+        metadata = {"category": "menu", "notes": "x" * 200}
+        with self.assertRaises(MetadataError) as raised:
+            fit_metadata("a", metadata, "text", max_bytes=120)
+        self.assertEqual(raised.exception.field, "notes")
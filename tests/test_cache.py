@@ -0,0 +1,110 @@
+import contextlib
+import io
+import itertools
+import os
+import tempfile
+import unittest
+from unittest import mock
+
+from mcp_pinecone.cache import CachedEmbedder, EmbeddingCache, main
+from mcp_pinecone.embeddings import HashingEmbedder
+
+MODEL = "hashing-4"
+
+
+class CountingEmbedder(HashingEmbedder):
+    def __init__(self, dimension: int):
+        super().__init__(dimension)
+        self.embedded = []
+
+    def embed_passages(self, texts):
+        self.embedded.extend(texts)
+        return super().embed_passages(texts)
+
+    def embed_query(self, text):
+        self.embedded.append(text)
+        return super().embed_query(text)
+
+
+class CacheTestCase(unittest.TestCase):
+    def setUp(self):
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        self.path = os.path.join(directory.name, "cache", "embeddings.db")
+        self.cache = EmbeddingCache(self.path, max_size_mb=1)
+        self.addCleanup(self.cache.conn.close)
+
+
+class EmbeddingCacheTest(CacheTestCase):
+    def test_round_trip_by_model_and_input_type(self):
+        self.cache.put_many(MODEL, "passage", ["refunds"], [[0.5, 0.25, 0.0, 1.0]])
+        self.assertEqual(
+            self.cache.get_many(MODEL, "passage", ["refunds", "returns"]),
+            [[0.5, 0.25, 0.0, 1.0], None],
+        )
+        self.assertEqual(self.cache.get_many(MODEL, "query", ["refunds"]), [None])
+        self.assertEqual(self.cache.get_many("other", "passage", ["refunds"]), [None])
+
+    @mock.patch("mcp_pinecone.cache.time.time", side_effect=itertools.count())
+    def test_evicts_least_recently_used(self, _):
+        # Each 4 float embedding takes 16 bytes
+        self.cache.max_bytes = 40
+        self.cache.put_many(MODEL, "passage", ["a"], [[1.0] * 4])
+        self.cache.put_many(MODEL, "passage", ["b"], [[1.0] * 4])
+        self.cache.get_many(MODEL, "passage", ["a"])
+        self.cache.put_many(MODEL, "passage", ["c"], [[1.0] * 4])
+
+        found = self.cache.get_many(MODEL, "passage", ["a", "b", "c"])
+        self.assertEqual([e is not None for e in found], [True, False, True])
+        self.assertEqual(self.cache.stats()["size_bytes"], 32)
+
+    def test_clear_by_model(self):
+        self.cache.put_many(MODEL, "passage", ["a", "b"], [[1.0] * 4] * 2)
+        self.cache.put_many("other", "query", ["a"], [[1.0] * 4])
+        self.assertEqual(self.cache.clear(MODEL), 2)
+        self.assertEqual(
+            self.cache.stats()["models"],
+            [{"model": "other", "input_type": "query", "entries": 1}],
+        )
+
+
+class CachedEmbedderTest(CacheTestCase):
+    def test_only_misses_are_embedded(self):
+        inner = CountingEmbedder(4)
+        embedder = CachedEmbedder(inner, self.cache, model=MODEL)
+
+        first = embedder.embed_passages(["refunds", "returns", "refunds"])
+        self.assertEqual(inner.embedded, ["refunds", "returns"])
+
+        second = embedder.embed_passages(["returns", "refunds", "shipping"])
+        self.assertEqual(inner.embedded, ["refunds", "returns", "shipping"])
+        self.assertEqual(second[:2], [first[1], first[0]])
+
+        embedder.embed_query("refunds")
+        embedder.embed_query("refunds")
+        self.assertEqual(inner.embedded[3:], ["refunds"])
+
+
+class CacheCommandTest(CacheTestCase):
+    def run_command(self, *args: str) -> str:
+        output = io.StringIO()
+        # Keep the developer's config file out of the settings
+        home = os.path.dirname(os.path.dirname(self.path))
+        with mock.patch.dict(os.environ, {"HOME": home}, clear=True):
+            with contextlib.redirect_stdout(output):
+                main(["--path", self.path, *args])
+        return output.getvalue()
+
+    def test_stats_and_clear(self):
+        self.cache.put_many(MODEL, "passage", ["a", "b"], [[1.0] * 4] * 2)
+        self.cache.put_many("other", "query", ["a"], [[1.0] * 4])
+
+        stats = self.run_command("stats")
+        self.assertIn("Entries: 3", stats)
+        self.assertIn(f"{MODEL} (passage): 2", stats)
+
+        self.assertEqual(
+            self.run_command("clear", "--model", "other"),
+            "Deleted 1 cached embeddings\n",
+        )
+        self.assertIn("Entries: 2", self.run_command("stats"))
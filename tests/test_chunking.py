@@ -0,0 +1,178 @@
+import unittest
+
+from mcp_pinecone.chunking import (
+    FixedSizeChunker,
+    MarkdownChunker,
+    ParagraphChunker,
+    RecursiveSplitter,
+    SentenceChunker,
+    breadcrumb,
+    content_budget,
+    create_chunker,
+    join_chunks,
+    merge_small,
+)
+from mcp_pinecone.config import ServerConfig
+from mcp_pinecone.utils import estimate_tokens
+
+CHINESE = "这是一个很长的中文段落，用来测试分块。" * 100
+
+
+class EstimateTokensTest(unittest.TestCase):
+    def test_words_and_punctuation(self):
+        self.assertEqual(estimate_tokens("hello world, ok"), 6)
+
+    def test_unspaced_scripts_count_every_character(self):
+        self.assertGreaterEqual(estimate_tokens(CHINESE), len(CHINESE))
+        self.assertGreaterEqual(estimate_tokens("สวัสดีครับ"), 10)
+
+
+class RecursiveSplitterTest(unittest.TestCase):
+    def test_pieces_stay_within_the_limit(self):
+        text = " ".join(f"Sentence number {i} is here." for i in range(50))
+        pieces = RecursiveSplitter(40).split(text)
+        self.assertGreater(len(pieces), 1)
+        for piece, overlap_chars in pieces:
+            self.assertLessEqual(estimate_tokens(piece), 40)
+            self.assertEqual(overlap_chars, 0)
+
+    def test_overlap_is_recorded(self):
+        text = " ".join(f"Sentence number {i} is here." for i in range(50))
+        pieces = RecursiveSplitter(40, overlap_tokens=8).split(text)
+        self.assertEqual(pieces[0][1], 0)
+        for (previous, _), (piece, overlap_chars) in zip(pieces, pieces[1:]):
+            self.assertGreater(overlap_chars, 0)
+            self.assertTrue(previous.endswith(piece[: overlap_chars - 1]))
+            self.assertLessEqual(estimate_tokens(piece), 40)
+
+    def test_unspaced_sentences(self):
+        pieces = [piece for piece, _ in RecursiveSplitter(100).split(CHINESE)]
+        self.assertEqual("".join(pieces), CHINESE)
+        for piece in pieces:
+            self.assertTrue(piece.endswith("。"))
+            self.assertLessEqual(estimate_tokens(piece), 100)
+
+
+class MarkdownChunkerTest(unittest.TestCase):
+    def test_chinese_chunks_fit_the_model_limit(self):
+        chunks = MarkdownChunker(max_tokens=507).chunk_document("zh", CHINESE)
+        self.assertGreater(len(chunks), 1)
+        for chunk in chunks:
+            self.assertLessEqual(estimate_tokens(chunk.content), 507)
+
+
+class MergeSmallTest(unittest.TestCase):
+    def test_small_sections_keep_their_headings(self):
+        sections = [
+            ("Setup starts here.", {"h1": "Guide", "h2": "Setup"}),
+            ("Run it.", {"h1": "Guide", "h2": "Usage"}),
+        ]
+        (merged,) = merge_small(sections, min_tokens=5, max_tokens=100)
+        text, headers = merged
+        self.assertEqual(text, "## Setup\nSetup starts here.\n\n## Usage\nRun it.")
+        self.assertEqual(headers, {"h1": "Guide"})
+
+
+class JoinChunksTest(unittest.TestCase):
+    def join(self, chunker, text):
+        chunks = chunker.chunk_document("doc", text)
+        return join_chunks([(chunk.content, chunk.metadata) for chunk in chunks])
+
+    def test_text_without_overlap_is_kept(self):
+        self.assertEqual(
+            join_chunks([("| a | b |", {"h1": "One"}), ("| c | d |", {"h1": "Two"})]),
+            "# One\n| a | b |\n\n# Two\n| c | d |",
+        )
+        self.assertEqual(
+            join_chunks([("It was done.\nDone", {}), ("Done is done.", {})]),
+            "It was done.\nDone\n\nDone is done.",
+        )
+
+    def test_overlap_is_removed(self):
+        text = " ".join(f"Sentence number {i} is here." for i in range(50))
+        chunker = SentenceChunker(max_tokens=40, overlap_tokens=8)
+        self.assertEqual(self.join(chunker, text).replace("\n\n", " "), text)
+
+    def test_merged_pieces_keep_their_overlap_exact(self):
+        sections = [
+            ("Setup starts here.", {"h1": "Guide", "h2": "Setup"}),
+            ("here. Setup ends.", {"h1": "Guide", "h2": "Setup", "overlap_chars": 6}),
+            ("Run it.", {"h1": "Guide", "h2": "Usage"}),
+        ]
+        first, merged = merge_small(sections, min_tokens=5, max_tokens=100)
+        self.assertEqual(merged[0], "## Setup\nhere. Setup ends.\n\n## Usage\nRun it.")
+        self.assertEqual(merged[1], {"h1": "Guide", "overlap_chars": 15})
+        self.assertEqual(
+            join_chunks([first, merged]),
+            "# Guide\n## Setup\nSetup starts here.\n\nSetup ends.\n\n## Usage\nRun it.",
+        )
+
+
+class CreateChunkerTest(unittest.TestCase):
+    config = ServerConfig.from_dict(
+        {
+            "chunking": {
+                "namespaces": {
+                    "changelogs": {"strategy": "markdown", "headers": ["##"]},
+                    "snippets": {"strategy": "fixed", "max_tokens": 64},
+                }
+            }
+        }
+    )
+
+    def test_config_strategy(self):
+        chunker = create_chunker(self.config)
+        self.assertIsInstance(chunker, MarkdownChunker)
+        self.assertEqual(chunker.max_tokens, self.config.chunk_max_tokens)
+
+    def test_namespace_settings(self):
+        chunker = create_chunker(self.config, "snippets")
+        self.assertIsInstance(chunker, FixedSizeChunker)
+        self.assertEqual(chunker.splitter.max_tokens, 64)
+
+    def test_call_overrides_namespace_strategy(self):
+        chunker = create_chunker(self.config, "changelogs", "paragraph")
+        self.assertIsInstance(chunker, ParagraphChunker)
+        chunker = create_chunker(self.config, "snippets", {"strategy": "sentence"})
+        self.assertIsInstance(chunker, SentenceChunker)
+        self.assertEqual(chunker.splitter.max_tokens, 64)
+
+    def test_call_parameters_must_apply(self):
+        with self.assertRaises(ValueError):
+            create_chunker(self.config, None, {"strategy": "fixed", "headers": ["#"]})
+        with self.assertRaises(ValueError):
+            create_chunker(self.config, None, "nonsense")
+        with self.assertRaises(ValueError):
+            create_chunker(self.config, None, {"max_tokens": 100, "overlap_tokens": 60})
+
+
+class BreadcrumbTest(unittest.TestCase):
+    def test_title_headers_and_symbol(self):
+        metadata = {"title": "Spec", "header_path": "Billing > Refunds", "symbol": "f"}
+        self.assertEqual(breadcrumb(metadata), "Spec > Billing > Refunds > f")
+        self.assertEqual(breadcrumb({"doc_id": "spec"}), "")
+
+    def test_long_breadcrumbs_keep_their_innermost_end(self):
+        path = " > ".join(f"Section {i}" for i in range(20))
+        text = breadcrumb({"title": "Spec", "header_path": path}, max_tokens=6)
+        self.assertLessEqual(estimate_tokens(text), 6)
+        self.assertTrue(text.endswith("Section 19"))
+        self.assertFalse(text.startswith(">"))
+
+    def test_budget_leaves_room_for_the_breadcrumb(self):
+        metadata = {"title": "Spec", "header_path": "Billing > Refunds"}
+        self.assertEqual(content_budget(100, metadata, False), 100)
+        context = estimate_tokens("Spec > Billing > Refunds")
+        self.assertEqual(content_budget(100, metadata, True), 100 - context)
+
+    def test_embedded_text_stays_within_the_limit(self):
+        text = "# Billing\n## Refunds\n" + " ".join(
+            f"Refund rule {i} applies." for i in range(60)
+        )
+        chunker = MarkdownChunker(max_tokens=40, overlap_tokens=0, breadcrumbs=True)
+        chunks = chunker.chunk_document("spec", text, {"title": "Spec"})
+        self.assertGreater(len(chunks), 1)
+        for chunk in chunks:
+            self.assertEqual(chunk.context, "Spec > Billing > Refunds")
+            self.assertNotIn("Spec >", chunk.content)
+            self.assertLessEqual(estimate_tokens(chunk.embedding_text), 40)
@@ -0,0 +1,127 @@
+import textwrap
+import unittest
+
+from mcp_pinecone.chunking import CodeChunker
+from mcp_pinecone.code import detect_language, segment_code
+
+PYTHON = textwrap.dedent(
+    '''\
+    import os
+
+    TIMEOUT = 30
+
+
+    # Retries are capped
+    @retry(times=3)
+    def fetch(url):
+        return os.path.join(url)
+
+
+    class Client:
+        """A small client"""
+
+        def __init__(self, url):
+            self.url = url
+
+        @property
+        def host(self):
+            return self.url.split("/")[2]
+    '''
+)
+
+GO = textwrap.dedent(
+    """\
+    package server
+
+    type Server struct {
+    \tAddr string
+    }
+
+    // Start listens on Addr
+    func (s *Server) Start() error {
+    \treturn nil
+    }
+
+    func New(addr string) *Server {
+    \treturn &Server{Addr: addr}
+    }
+    """
+)
+
+
+def outline(source: str, language: str, max_tokens: int = 512) -> list:
+    return [
+        (segment.kind, segment.symbol, segment.start_line, segment.end_line)
+        for segment in segment_code(source, language, max_tokens)
+    ]
+
+
+class SegmentCodeTest(unittest.TestCase):
+    def test_detect_language(self):
+        self.assertEqual(detect_language("src/app/Main.TSX"), "typescript")
+        self.assertEqual(detect_language("lib.rs"), "rust")
+        self.assertIsNone(detect_language("README.md"))
+        self.assertIsNone(detect_language(None))
+
+    def test_python_definitions_keep_comments_and_decorators(self):
+        self.assertEqual(
+            outline(PYTHON, "python"),
+            [
+                ("module", None, 1, 3),
+                ("function", "fetch", 6, 9),
+                ("class", "Client", 12, 20),
+            ],
+        )
+
+    def test_large_python_classes_split_into_methods(self):
+        self.assertEqual(
+            outline(PYTHON, "python", max_tokens=20)[2:],
+            [
+                ("class", "Client", 12, 13),
+                ("method", "Client.__init__", 15, 16),
+                ("method", "Client.host", 18, 20),
+            ],
+        )
+
+    def test_invalid_python_is_one_segment(self):
+        self.assertEqual(
+            outline("def broken(:\n    pass\n", "python"), [("module", None, 1, 2)]
+        )
+
+    def test_go_methods_are_named_by_receiver(self):
+        self.assertEqual(
+            outline(GO, "go"),
+            [
+                ("module", None, 1, 1),
+                ("type", "Server", 3, 5),
+                ("method", "Server.Start", 7, 10),
+                ("function", "New", 12, 14),
+            ],
+        )
+
+    def test_typescript_declarations(self):
+        source = "export interface User {\n  id: string\n}\n\n"
+        source += "export const load = async (id: string) => {\n  return id\n}\n"
+        self.assertEqual(
+            outline(source, "typescript"),
+            [("interface", "User", 1, 3), ("function", "load", 5, 7)],
+        )
+
+
+class CodeChunkerTest(unittest.TestCase):
+    def test_chunks_record_symbols_and_line_ranges(self):
+        chunks = CodeChunker(max_tokens=512).chunk_document("client.py", PYTHON)
+        lines = PYTHON.split("\n")
+        self.assertEqual(
+            [(c.metadata["kind"], c.metadata.get("symbol")) for c in chunks],
+            [("module", None), ("function", "fetch"), ("class", "Client")],
+        )
+        for chunk in chunks:
+            self.assertEqual(chunk.metadata["language"], "python")
+            start, end = chunk.metadata["start_line"], chunk.metadata["end_line"]
+            self.assertEqual(chunk.content, "\n".join(lines[start - 1 : end]))
+
+    def test_language_from_metadata(self):
+        metadata = {"language": "rust"}
+        (chunk,) = CodeChunker().chunk_document("snippet", "fn main() {}", metadata)
+        self.assertEqual(chunk.metadata["symbol"], "main")
@@ -0,0 +1,121 @@
+import os
+import tempfile
+import unittest
+from unittest import mock
+
+from mcp_pinecone.config import ServerConfig, load_config
+
+LOCAL_HOST = {"host": "http://localhost:5081"}
+
+
+class ValidateTest(unittest.TestCase):
+    def validate(self, data: dict) -> ServerConfig:
+        config = ServerConfig.from_dict(data)
+        config.validate()
+        return config
+
+    def test_cloud_needs_an_api_key(self):
+        with self.assertRaisesRegex(ValueError, "API key is required"):
+            self.validate({})
+        self.validate({"pinecone": {"api_key": "key"}})
+
+    def test_inference_needs_an_api_key(self):
+        for data in [
+            {"pinecone": LOCAL_HOST},
+            {"backend": "memory", "pinecone": {"embedder": "pinecone"}},
+            {"backend": "memory", "sparse": {"encoder": "pinecone"}},
+            {"backend": "memory", "rerank": {"provider": "pinecone"}},
+        ]:
+            with self.assertRaisesRegex(ValueError, "requires an API key"):
+                self.validate(data)
+
+    def test_local_endpoint_without_api_key(self):
+        config = self.validate({"pinecone": {**LOCAL_HOST, "embedder": "hashing"}})
+        self.assertEqual(config.rerank_provider, "local")
+        self.validate(
+            {
+                "pinecone": {
+                    "control_plane_host": "http://localhost:5080",
+                    "embedder": "hashing",
+                }
+            }
+        )
+        config = self.validate({"pinecone": {"api_key": "key"}})
+        self.assertEqual(config.rerank_provider, "pinecone")
+
+
+CONFIG_FILE = """
+top_k = 5
+backend = "sqlite"
+
+[pinecone]
+index_name = "notes"
+cloud = "gcp"
+
+[profiles.work.pinecone]
+index_name = "work-notes"
+"""
+
+
+class LoadConfigTest(unittest.TestCase):
+    def setUp(self):
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        self.path = os.path.join(directory.name, "config.toml")
+        with open(self.path, "w") as f:
+            f.write(CONFIG_FILE)
+
+        # Keep the developer's environment and .env out of the layers
+        patches = [
+            mock.patch.dict(os.environ, {"MCP_PINECONE_CONFIG": self.path}, clear=True),
+            mock.patch("mcp_pinecone.config.load_dotenv"),
+        ]
+        for patch in patches:
+            patch.start()
+            self.addCleanup(patch.stop)
+
+    def test_file_values(self):
+        config = load_config([])
+        self.assertEqual(config.backend, "sqlite")
+        self.assertEqual(config.top_k, 5)
+        self.assertEqual(config.pinecone.index_name, "notes")
+
+    def test_env_values(self):
+        os.environ.update(
+            {"MCP_PINECONE_BACKEND": "memory", "PINECONE_INDEX_NAME": "env-notes"}
+        )
+        config = load_config([])
+        self.assertEqual(config.backend, "memory")
+        self.assertEqual(config.pinecone.index_name, "env-notes")
+
+    def test_cli_overrides_env(self):
+        os.environ["MCP_PINECONE_BACKEND"] = "sqlite"
+        config = load_config(["--backend", "memory", "--index-name", "cli-notes"])
+        self.assertEqual(config.backend, "memory")
+        self.assertEqual(config.pinecone.index_name, "cli-notes")
+
+    def test_profile_overrides_top_level(self):
+        config = load_config(["--profile", "work"])
+        self.assertEqual(config.pinecone.index_name, "work-notes")
+        self.assertEqual(config.pinecone.cloud, "gcp")
+
+    def test_env_overrides_file_and_cli_overrides_env(self):
+        os.environ.update({"MCP_PINECONE_TOP_K": "8", "MCP_PINECONE_BACKEND": "sqlite"})
+        config = load_config(["--profile", "work", "--backend", "memory"])
+        self.assertEqual(config.top_k, 8)
+        self.assertEqual(config.backend, "memory")
+        self.assertEqual(config.pinecone.index_name, "work-notes")
+
+    def test_unknown_profile(self):
+        with self.assertRaisesRegex(ValueError, "Available: work"):
+            load_config(["--profile", "home"])
+
+    def test_missing_explicit_file(self):
+        with self.assertRaisesRegex(ValueError, "Config file not found"):
+            load_config(["--config", self.path + ".missing"])
+
+    def test_pinecone_backend_needs_an_api_key(self):
+        with self.assertRaisesRegex(ValueError, "API key is required"):
+            load_config(["--backend", "pinecone"])
+        config = load_config(["--backend", "pinecone", "--api-key", "key"])
+        self.assertEqual(config.pinecone.api_key, "key")
@@ -0,0 +1,256 @@
+import base64
+import os
+import tempfile
+import unittest
+
+from mcp_pinecone import server
+from mcp_pinecone.backends import (
+    create_vector_store,
+    fetch_metadata,
+    list_chunk_ids,
+    list_record_ids,
+)
+from mcp_pinecone.blobs import BlobStore
+from mcp_pinecone.config import ServerConfig
+
+SETUP = " ".join(f"Sentence number {i} is here." for i in range(20))
+
+BODY = f"""# Guide
+Intro text.
+## Setup
+{SETUP}
+### Linux
+Use apt.
+## Usage
+Run it."""
+
+GUIDE = f"""---
+title: Guide
+date: 2024-05-01
+---
+{BODY}"""
+
+ALPHA = "# Alpha\nThe first section of the spec."
+SPEC = f"{ALPHA}\n# Beta\nThe second section of the spec."
+
+
+def words(text: str) -> str:
+    # Reassembly may differ from the original in blank lines only
+    return " ".join(text.split())
+
+
+class DocumentTests:
+    """Document tools run end to end against a local backend."""
+
+    backend = ""
+
+    def setUp(self):
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        config = ServerConfig.from_dict(
+            {
+                "backend": self.backend,
+                "database_path": os.path.join(directory.name, "test.db"),
+                "chunking": {"max_tokens": 40, "min_tokens": 5, "overlap_tokens": 8},
+            }
+        )
+        blob_store = BlobStore(os.path.join(directory.name, "blobs"))
+        vector_store = create_vector_store(config, blob_store=blob_store)
+        if hasattr(vector_store, "conn"):
+            self.addCleanup(vector_store.conn.close)
+
+        for name, value in [
+            ("server_config", config),
+            ("blob_store", blob_store),
+            ("vector_store", vector_store),
+        ]:
+            self.addCleanup(setattr, server, name, getattr(server, name))
+            setattr(server, name, value)
+        self.store = vector_store
+
+    async def call(self, name: str, arguments: dict) -> str:
+        return (await server.handle_call_tool(name, arguments))[0].text
+
+    async def read(self, document_id: str) -> str:
+        text = await self.call("read-document", {"document_id": document_id})
+        return text.split("Content:\n", 1)[1]
+
+    async def test_upsert_and_read_round_trip(self):
+        await self.call("upsert-document", {"id": "guide", "text": GUIDE})
+        self.assertEqual(words(await self.read("guide")), words(BODY))
+
+    async def test_upsert_and_read_chunks(self):
+        await self.call("upsert-document", {"id": "guide", "text": GUIDE})
+        chunk_ids = list_chunk_ids(self.store, "guide")
+        self.assertGreater(len(chunk_ids), 1)
+
+        text = await self.call("read-document", {"document_id": chunk_ids[-1]})
+        self.assertIn("Run it.", text)
+
+    async def test_search_finds_the_matching_chunk(self):
+        await self.call("upsert-document", {"id": "guide", "text": GUIDE})
+        text = await self.call("semantic-search", {"query": "apt", "top_k": 1})
+        self.assertIn("Use apt.", text)
+
+    async def test_chunk_metadata_is_flattened(self):
+        metadata = {"team": "docs", "doc_id": "other"}
+        await self.call(
+            "upsert-document", {"id": "guide", "text": GUIDE, "metadata": metadata}
+        )
+        chunk_ids = list_chunk_ids(self.store, "guide")
+        chunks = fetch_metadata(self.store, chunk_ids)
+        paths = set()
+        for number, chunk_id in enumerate(chunk_ids, 1):
+            chunk = chunks[chunk_id]
+            self.assertEqual(chunk["doc_id"], "guide")
+            self.assertEqual(chunk["team"], "docs")
+            self.assertEqual(chunk["chunk_number"], number)
+            self.assertEqual(chunk["total_chunks"], len(chunk_ids))
+            paths.add(chunk.get("header_path"))
+        self.assertIn("Guide > Setup", paths)
+
+    async def test_date_metadata_becomes_epoch_seconds(self):
+        metadata = {"author": {"name": "Ada"}, "draft": None}
+        await self.call(
+            "upsert-document", {"id": "guide", "text": GUIDE, "metadata": metadata}
+        )
+        chunk_ids = list_chunk_ids(self.store, "guide")
+        for metadata in fetch_metadata(self.store, chunk_ids).values():
+            self.assertEqual(metadata["date"], 1714521600)
+            self.assertEqual(metadata["title"], "Guide")
+            self.assertEqual(metadata["author.name"], "Ada")
+            self.assertNotIn("draft", metadata)
+
+    async def test_front_matter_dates_elsewhere_stay_as_written(self):
+        text = "---\ntitle: 2024-05-01\n---\n# Notes\nWritten on the day."
+        await self.call("upsert-document", {"id": "notes", "text": text})
+        (chunk_id,) = list_chunk_ids(self.store, "notes")
+        metadata = fetch_metadata(self.store, [chunk_id])[chunk_id]
+        self.assertEqual(metadata["title"], "2024-05-01")
+
+    async def test_resources_are_named_by_title(self):
+        await self.call("upsert-document", {"id": "guide", "text": GUIDE})
+        resources = await server.handle_list_resources()
+        self.assertEqual({resource.name for resource in resources}, {"Guide"})
+
+    async def test_unsupported_metadata_is_rejected(self):
+        metadata = {"authors": [{"name": "Ada"}]}
+        with self.assertRaisesRegex(ValueError, "authors"):
+            await self.call(
+                "upsert-document", {"id": "guide", "text": GUIDE, "metadata": metadata}
+            )
+        self.assertEqual(list_chunk_ids(self.store, "guide"), [])
+
+    async def test_binary_payload_is_read_from_the_blob_store(self):
+        payload = b"\x89PNG\r\n\x1a\n"
+        await self.call(
+            "upsert-document",
+            {
+                "id": "logo",
+                "text": "The company logo.",
+                "content": base64.b64encode(payload).decode(),
+                "metadata": {"content_type": "image/png"},
+            },
+        )
+        (chunk_id,) = list_chunk_ids(self.store, "logo")
+        uri = f"pinecone://vectors/{chunk_id}"
+        self.assertEqual(await server.handle_read_resource(uri), payload)
+
+    async def test_merged_small_sections_keep_their_headings(self):
+        text = "# Title\nIntro.\n## Small\nTiny one.\n## Also small\nTiny two."
+        await self.call("upsert-document", {"id": "notes", "text": text})
+        chunk_ids = list_chunk_ids(self.store, "notes")
+        self.assertEqual(len(chunk_ids), 1)
+        chunk = fetch_metadata(self.store, chunk_ids)[chunk_ids[0]]
+        self.assertIn("## Small\nTiny one.", chunk["text"])
+        self.assertIn("## Also small\nTiny two.", chunk["text"])
+        self.assertEqual(words(await self.read("notes")), words(text))
+
+    async def test_reupsert_skips_chunks_that_only_moved(self):
+        parts = [f"# Part {i}\nThis is part {i} of the document." for i in range(6)]
+        await self.call("upsert-document", {"id": "doc", "text": "\n".join(parts[:5])})
+        text = "\n".join(parts)
+        result = await self.call("upsert-document", {"id": "doc", "text": text})
+        self.assertIn("(1 added, 0 updated, 5 unchanged, 0 removed)", result)
+        chunks = fetch_metadata(self.store, list_chunk_ids(self.store, "doc"))
+        self.assertEqual(len(chunks), 6)
+        for metadata in chunks.values():
+            self.assertEqual(metadata["total_chunks"], 6)
+
+    async def test_reupsert_removes_only_its_own_stale_chunks(self):
+        await self.call("upsert-document", {"id": "spec", "text": SPEC})
+        await self.call("upsert-document", {"id": "spec#v2", "text": SPEC})
+        await self.call("upsert-document", {"id": "spec", "text": ALPHA})
+        self.assertEqual(len(list_chunk_ids(self.store, "spec")), 1)
+        self.assertEqual(len(list_chunk_ids(self.store, "spec#v2")), 2)
+        self.assertEqual(len(list_record_ids(self.store)), 3)
+
+    async def test_list_documents(self):
+        await self.call("upsert-document", {"id": "guide", "text": GUIDE})
+        await self.call("upsert-document", {"id": "spec", "text": SPEC})
+        guide_chunks = len(list_chunk_ids(self.store, "guide"))
+
+        first = await self.call("list-documents", {"limit": 1})
+        self.assertIn(f"- guide ({guide_chunks} chunks): Guide\n", first)
+        cursor = first.rsplit("pass cursor: ", 1)[1].strip()
+
+        second = await self.call("list-documents", {"limit": 1, "cursor": cursor})
+        self.assertEqual(second, "Documents (1):\n\n- spec (2 chunks)\n")
+
+    async def test_describe_index_and_list_namespaces(self):
+        await self.call("upsert-document", {"id": "spec", "text": SPEC})
+        await self.call(
+            "upsert-document", {"id": "spec", "text": ALPHA, "namespace": "drafts"}
+        )
+
+        text = await self.call("list-namespaces", {})
+        self.assertEqual(
+            text, "Namespaces (2):\n- (default): 2 vectors\n- drafts: 1 vector"
+        )
+        text = await self.call("describe-index", {})
+        self.assertIn("Total vectors: 3\n", text)
+        self.assertIn(f"Backend: {self.backend}\n", text)
+
+    async def test_delete_document(self):
+        await self.call("upsert-document", {"id": "spec", "text": SPEC})
+        await self.call("upsert-document", {"id": "spec#v2", "text": SPEC})
+        kept = list_chunk_ids(self.store, "spec#v2")
+
+        dry_run = await self.call("delete-document", {"id": "spec", "dry_run": True})
+        self.assertNotIn("spec#v2", dry_run)
+        self.assertEqual(len(list_record_ids(self.store)), 4)
+
+        await self.call("delete-document", {"id": "spec"})
+        self.assertEqual(list_record_ids(self.store), kept)
+        with self.assertRaises(ValueError):
+            await self.read("spec")
+
+    async def test_search_filters(self):
+        await self.call(
+            "upsert-document",
+            {"id": "guide", "text": GUIDE, "metadata": {"category": "docs"}},
+        )
+        await self.call(
+            "upsert-document",
+            {"id": "spec", "text": SPEC, "metadata": {"category": "specs"}},
+        )
+        arguments = {"query": "the spec", "top_k": 10}
+        text = await self.call("semantic-search", {**arguments, "category": "docs"})
+        self.assertIn("guide#", text)
+        self.assertNotIn("spec#", text)
+        day = {"start": "2024-05-01", "end": "2024-05-01"}
+        text = await self.call("semantic-search", {**arguments, "date_range": day})
+        self.assertIn("guide#", text)
+        self.assertNotIn("spec#", text)
+
+    async def test_read_missing_document(self):
+        with self.assertRaises(ValueError):
+            await self.call("read-document", {"document_id": "missing"})
+
+
+class InMemoryDocumentTest(DocumentTests, unittest.IsolatedAsyncioTestCase):
+    backend = "memory"
+
+
+class SQLiteDocumentTest(DocumentTests, unittest.IsolatedAsyncioTestCase):
+    backend = "sqlite"
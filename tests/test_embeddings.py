@@ -0,0 +1,74 @@
+import unittest
+from unittest import mock
+
+from mcp_pinecone.config import PineconeConfig
+from mcp_pinecone.embeddings import HashingEmbedder, PineconeEmbedder, batch_texts
+
+
+class HashingEmbedderTest(unittest.TestCase):
+    def test_embeddings_are_normalized_and_deterministic(self):
+        embedder = HashingEmbedder(16)
+        vector = embedder.embed("Refunds are issued within 30 days")
+        self.assertAlmostEqual(sum(v * v for v in vector), 1.0)
+        query = embedder.embed_query("refunds are issued within 30 days")
+        self.assertEqual(vector, query)
+
+    def test_text_without_tokens_embeds_as_zeros(self):
+        self.assertEqual(HashingEmbedder(4).embed("--- 🎉"), [0.0] * 4)
+
+
+class BatchTextsTest(unittest.TestCase):
+    def test_batches_respect_input_and_token_limits(self):
+        texts = ["one two three four"] * 5
+        batches = batch_texts(texts, 2, 100)
+        self.assertEqual([len(batch) for batch in batches], [2, 2, 1])
+        # Each text counts as at most max_tokens_per_input tokens
+        self.assertEqual(
+            [len(batch) for batch in batch_texts(texts, 10, 3, max_batch_tokens=9)],
+            [3, 2],
+        )
+
+
+class RateLimited(Exception):
+    status = 429
+
+
+@mock.patch("mcp_pinecone.pinecone.Pinecone")
+class PineconeEmbedderTest(unittest.TestCase):
+    def input_types(self, sdk) -> list:
+        calls = sdk.return_value.inference.embed.call_args_list
+        return [call.kwargs["parameters"]["input_type"] for call in calls]
+
+    def respond(self, sdk):
+        embed = sdk.return_value.inference.embed
+        embed.side_effect = lambda inputs, **kwargs: mock.Mock(
+            data=[mock.Mock(values=[1.0]) for _ in inputs]
+        )
+        return embed
+
+    def test_queries_and_passages_are_embedded_differently(self, sdk):
+        self.respond(sdk)
+        embedder = PineconeEmbedder(PineconeConfig(api_key="key"))
+
+        self.assertEqual(embedder.embed_passages(["a", "b"]), [[1.0], [1.0]])
+        self.assertEqual(embedder.embed_query("a"), [1.0])
+        self.assertEqual(self.input_types(sdk), ["passage", "query"])
+
+    def test_passages_are_batched(self, sdk):
+        embed = self.respond(sdk)
+        embedder = PineconeEmbedder(PineconeConfig(api_key="key"))
+        embedder.limits = {"max_inputs": 2, "max_tokens_per_input": 507}
+
+        self.assertEqual(len(embedder.embed_passages(["text"] * 5)), 5)
+        sizes = [len(call.kwargs["inputs"]) for call in embed.call_args_list]
+        self.assertEqual(sizes, [2, 2, 1])
+
+    @mock.patch("mcp_pinecone.embeddings.time.sleep")
+    def test_rate_limited_requests_are_retried(self, sleep, sdk):
+        embed = sdk.return_value.inference.embed
+        response = mock.Mock(data=[mock.Mock(values=[1.0])])
+        embed.side_effect = [RateLimited(), response]
+        embedder = PineconeEmbedder(PineconeConfig(api_key="key"))
+
+        self.assertEqual(embedder.embed_passages(["text"]), [[1.0]])
+        sleep.assert_called_once_with(1)
@@ -0,0 +1,73 @@
+import unittest
+
+from mcp_pinecone.filters import build_search_filter, matches_filter
+
+
+class MatchesFilterTest(unittest.TestCase):
+    metadata = {"genre": "drama", "year": 2020, "tags": ["a", "b"]}
+
+    def test_empty_filter_matches(self):
+        self.assertTrue(matches_filter(self.metadata, None))
+        self.assertTrue(matches_filter(self.metadata, {}))
+
+    def test_equality(self):
+        self.assertTrue(matches_filter(self.metadata, {"genre": "drama"}))
+        self.assertTrue(matches_filter(self.metadata, {"genre": {"$eq": "drama"}}))
+        self.assertFalse(matches_filter(self.metadata, {"genre": {"$ne": "drama"}}))
+
+    def test_ranges(self):
+        self.assertTrue(matches_filter(self.metadata, {"year": {"$gte": 2020}}))
+        self.assertTrue(matches_filter(self.metadata, {"year": {"$lt": 2021}}))
+        self.assertFalse(matches_filter(self.metadata, {"year": {"$gt": 2020}}))
+        self.assertFalse(matches_filter(self.metadata, {"genre": {"$lte": 5}}))
+
+    def test_list_values_match_any_element(self):
+        self.assertTrue(matches_filter(self.metadata, {"tags": "b"}))
+        self.assertTrue(matches_filter(self.metadata, {"tags": {"$in": ["b", "c"]}}))
+        self.assertFalse(matches_filter(self.metadata, {"tags": {"$nin": ["a"]}}))
+
+    def test_missing_fields(self):
+        self.assertFalse(matches_filter(self.metadata, {"author": "Ada"}))
+        self.assertTrue(matches_filter(self.metadata, {"author": {"$ne": "Ada"}}))
+        self.assertTrue(matches_filter(self.metadata, {"author": {"$exists": False}}))
+        self.assertFalse(matches_filter(self.metadata, {"author": {"$exists": True}}))
+
+    def test_logical_operators(self):
+        either = {"$or": [{"genre": "comedy"}, {"year": 2020}]}
+        both = {"$and": [{"genre": "comedy"}, {"year": 2020}]}
+        self.assertTrue(matches_filter(self.metadata, either))
+        self.assertFalse(matches_filter(self.metadata, both))
+
+    def test_unsupported_operators_raise(self):
+        with self.assertRaises(ValueError):
+            matches_filter(self.metadata, {"$not": {"genre": "drama"}})
+        with self.assertRaises(ValueError):
+            matches_filter(self.metadata, {"genre": {"$regex": "d.*"}})
+        with self.assertRaises(ValueError):
+            matches_filter(self.metadata, {"genre": {"$in": "drama"}})
+
+
+class BuildSearchFilterTest(unittest.TestCase):
+    def test_nothing_to_filter(self):
+        self.assertIsNone(build_search_filter())
+
+    def test_conditions_are_combined(self):
+        self.assertEqual(
+            build_search_filter(category="docs", tags=["a"]),
+            {"$and": [{"category": {"$eq": "docs"}}, {"tags": {"$in": ["a"]}}]},
+        )
+
+    def test_single_tag(self):
+        self.assertEqual(build_search_filter(tags="a"), {"tags": {"$in": ["a"]}})
+
+    def test_invalid_tags_raise(self):
+        for tags in (5, ["a", 1], {"a": 1}):
+            with self.assertRaises(ValueError):
+                build_search_filter(tags=tags)
+
+    def test_date_only_end_is_inclusive(self):
+        day = {"start": "2024-01-01", "end": "2024-01-01"}
+        self.assertEqual(
+            build_search_filter(date_range=day),
+            {"date": {"$gte": 1704067200, "$lte": 1704067200 + 86399}},
+        )
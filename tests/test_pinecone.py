@@ -0,0 +1,66 @@
+import unittest
+from unittest import mock
+
+from mcp_pinecone.config import PineconeConfig
+from mcp_pinecone.constants import LOCAL_API_KEY
+from mcp_pinecone.embeddings import HashingEmbedder
+from mcp_pinecone.pinecone import PineconeClient, pinecone_client
+
+
+class FailingIndex:
+    def list_paginated(self, **kwargs):
+        raise ConnectionError("Connection reset")
+
+
+class PineconeClientTest(unittest.TestCase):
+    def client(self, index) -> PineconeClient:
+        # Skip connecting, only the index is used
+        client = PineconeClient.__new__(PineconeClient)
+        client.index = index
+        return client
+
+    def test_list_errors_are_raised(self):
+        # An empty page would read as a document without chunks
+        with self.assertRaises(ConnectionError):
+            self.client(FailingIndex()).list_records(prefix="doc#")
+
+
+@mock.patch("mcp_pinecone.pinecone.Pinecone")
+class PineconeEndpointTest(unittest.TestCase):
+    def test_local_control_plane(self, sdk):
+        pc = sdk.return_value
+        pc.list_indexes.return_value = [{"name": "notes"}]
+        pc.describe_index.return_value.host = "localhost:5081"
+        pc.describe_index.return_value.dimension = 8
+
+        config = PineconeConfig(
+            index_name="notes", control_plane_host="http://localhost:5080"
+        )
+        PineconeClient(config, embedder=HashingEmbedder(8))
+        sdk.assert_called_once_with(
+            api_key=LOCAL_API_KEY, host="http://localhost:5080"
+        )
+        # The index host gets the control plane's scheme
+        pc.Index.assert_called_once_with(name="notes", host="http://localhost:5081")
+
+    def test_data_plane_host_skips_index_checks(self, sdk):
+        pc = sdk.return_value
+        config = PineconeConfig(index_name="notes", host="http://localhost:5081")
+        PineconeClient(config, embedder=HashingEmbedder(8))
+        sdk.assert_called_once_with(api_key=LOCAL_API_KEY)
+        pc.list_indexes.assert_not_called()
+        pc.Index.assert_called_once_with(name="notes", host="http://localhost:5081")
+
+
+class PineconeClientFactoryTest(unittest.TestCase):
+    @mock.patch("mcp_pinecone.pinecone.Pinecone")
+    def test_cloud(self, sdk):
+        pinecone_client(PineconeConfig(api_key="key"))
+        sdk.assert_called_once_with(api_key="key")
+
+    @mock.patch("mcp_pinecone.pinecone.Pinecone")
+    def test_local_control_plane(self, sdk):
+        pinecone_client(PineconeConfig(control_plane_host="http://localhost:5080"))
+        sdk.assert_called_once_with(
+            api_key=LOCAL_API_KEY, host="http://localhost:5080"
+        )
@@ -0,0 +1,62 @@
+import unittest
+from types import SimpleNamespace
+from unittest import mock
+
+from mcp_pinecone.rerank import PineconeReranker, rerank_matches
+
+
+class KeywordReranker:
+    """Scores documents by how many query words they contain."""
+
+    def score(self, query, documents):
+        words = query.lower().split()
+        return [float(sum(w in d.lower() for w in words)) for d in documents]
+
+
+def match(record_id: str, score: float, text: str) -> dict:
+    return {"id": record_id, "score": score, "metadata": {"text": text}}
+
+
+class RerankMatchesTest(unittest.TestCase):
+    MATCHES = [
+        match("shipping", 0.9, "Shipping takes five days"),
+        match("refunds", 0.8, "Refunds are issued within 30 days"),
+        match("returns", 0.7, "Returns need a refund form"),
+        match("empty", 0.6, ""),
+    ]
+
+    def test_orders_by_rerank_score_and_keeps_vector_score(self):
+        reranked = rerank_matches(KeywordReranker(), "refund days", self.MATCHES, 3)
+        ids = [m["id"] for m in reranked]
+        self.assertEqual(ids, ["refunds", "shipping", "returns"])
+        self.assertEqual(
+            [(m["score"], m["rerank_score"]) for m in reranked],
+            [(0.8, 2.0), (0.9, 1.0), (0.7, 1.0)],
+        )
+
+    def test_matches_without_metadata(self):
+        reranked = rerank_matches(KeywordReranker(), "refund", [{"id": "a"}], 1)
+        self.assertEqual(reranked, [{"id": "a", "rerank_score": 0.0}])
+
+
+class PineconeRerankerTest(unittest.TestCase):
+    @mock.patch("mcp_pinecone.rerank.RERANK_MAX_DOCUMENTS", 2)
+    def test_scores_in_input_order_across_batches(self):
+        def rerank(model, query, documents, **kwargs):
+            scores = KeywordReranker().score(query, documents)
+            # The API answers best first
+            ranked = sorted(enumerate(scores), key=lambda item: -item[1])
+            return SimpleNamespace(
+                data=[SimpleNamespace(index=i, score=s) for i, s in ranked]
+            )
+
+        reranker = PineconeReranker.__new__(PineconeReranker)
+        reranker.model = "bge-reranker-v2-m3"
+        reranker.pc = mock.Mock()
+        reranker.pc.inference.rerank.side_effect = rerank
+
+        documents = ["shipping", "refund days", "days", "refund"]
+        self.assertEqual(
+            reranker.score("refund days", documents), [0.0, 2.0, 1.0, 1.0]
+        )
+        self.assertEqual(reranker.pc.inference.rerank.call_count, 2)
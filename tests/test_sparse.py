@@ -0,0 +1,113 @@
+import math
+import unittest
+from unittest import mock
+
+from mcp_pinecone.embeddings import HashingEmbedder
+from mcp_pinecone.memory_store import InMemoryVectorStore
+from mcp_pinecone.pinecone import PineconeClient, PineconeRecord
+from mcp_pinecone.sparse import BM25Encoder, hybrid_score, sparse_dot
+
+DIMENSION = 64
+
+
+class BM25EncoderTest(unittest.TestCase):
+    def setUp(self):
+        self.encoder = BM25Encoder()
+
+    def test_codes_stay_whole(self):
+        self.assertEqual(
+            self.encoder.tokenize("Error E-1042 in v2.3.1"),
+            ["error", "e-1042", "in", "v2.3.1"],
+        )
+
+    def test_vectors_are_normalized(self):
+        query = self.encoder.encode_query("refund refund policy")
+        (document,) = self.encoder.encode_documents(["refund refund policy"])
+        for vector in (query, document):
+            self.assertAlmostEqual(math.sqrt(sum(v * v for v in vector["values"])), 1)
+            self.assertEqual(vector["indices"], sorted(vector["indices"]))
+        self.assertEqual(len(query["indices"]), 2)
+
+    def test_term_frequency_saturates(self):
+        (document,) = self.encoder.encode_documents(["refund refund refund policy"])
+        refund, policy = (
+            dict(zip(document["indices"], document["values"]))[self.encoder.index(t)]
+            for t in ("refund", "policy")
+        )
+        self.assertGreater(refund, policy)
+        self.assertLess(refund, 3 * policy)
+
+    def test_text_without_terms(self):
+        self.assertEqual(self.encoder.encode_query("!?"), {"indices": [], "values": []})
+
+
+class HybridScoreTest(unittest.TestCase):
+    QUERY = {"indices": [1, 2], "values": [0.6, 0.8]}
+
+    def test_weighted_by_alpha(self):
+        record = {"indices": [2, 3], "values": [0.5, 0.5]}
+        self.assertAlmostEqual(sparse_dot(self.QUERY, record), 0.4)
+        self.assertAlmostEqual(hybrid_score(0.9, self.QUERY, record, 0.75), 0.775)
+        self.assertAlmostEqual(hybrid_score(0.9, self.QUERY, record, 0.0), 0.4)
+
+    def test_dense_only_without_sparse_query(self):
+        self.assertEqual(hybrid_score(0.9, None, None, 0.5), 0.9)
+
+    def test_records_without_sparse_values_score_zero_sparse(self):
+        self.assertAlmostEqual(hybrid_score(0.9, self.QUERY, None, 0.5), 0.45)
+
+
+class HybridSearchTest(unittest.TestCase):
+    def setUp(self):
+        embedder = HashingEmbedder(DIMENSION)
+        self.store = InMemoryVectorStore(
+            DIMENSION, embedder=embedder, sparse_encoder=BM25Encoder()
+        )
+        texts = {
+            "sync": "Sync fails with error E-1042 after an upgrade",
+            "timeouts": "Sync failures are usually network timeouts",
+        }
+        self.store.upsert_records(
+            [
+                PineconeRecord(
+                    id=record_id,
+                    embedding=embedder.embed(text),
+                    text=text,
+                    metadata={"text": text},
+                )
+                for record_id, text in texts.items()
+            ]
+        )
+
+    def scores(self, query: str, alpha=None):
+        matches = self.store.search_records(query, alpha=alpha)["matches"]
+        return {match["id"]: match["score"] for match in matches}
+
+    def test_keyword_only(self):
+        scores = self.scores("E-1042", alpha=0.0)
+        self.assertGreater(scores["sync"], 0)
+        self.assertEqual(scores["timeouts"], 0)
+
+    def test_semantic_only(self):
+        dense = self.scores("sync failures")
+        self.assertEqual(self.scores("sync failures", alpha=1.0), dense)
+
+
+class PineconeHybridQueryTest(unittest.TestCase):
+    def test_vectors_are_weighted_by_alpha(self):
+        # Skip connecting, only the index, embedder and encoder are used
+        client = PineconeClient.__new__(PineconeClient)
+        client.index = mock.Mock()
+        client.embedder = HashingEmbedder(DIMENSION)
+        client.sparse_encoder = BM25Encoder()
+
+        client.search_records("refund", alpha=0.25)
+
+        query = client.index.query.call_args.kwargs
+        dense = client.embedder.embed_query("refund")
+        sparse = client.sparse_encoder.encode_query("refund")
+        self.assertEqual(query["vector"], [v * 0.25 for v in dense])
+        self.assertEqual(query["sparse_vector"]["indices"], sparse["indices"])
+        self.assertEqual(
+            query["sparse_vector"]["values"], [v * 0.75 for v in sparse["values"]]
+        )
@@ -0,0 +1,144 @@
+import os
+import tempfile
+import unittest
+
+from mcp_pinecone.backends import (
+    fetch_metadata,
+    list_chunk_ids,
+    list_documents,
+    list_record_ids,
+)
+from mcp_pinecone.embeddings import HashingEmbedder
+from mcp_pinecone.memory_store import InMemoryVectorStore
+from mcp_pinecone.pinecone import PineconeRecord
+from mcp_pinecone.sqlite_store import SQLiteVectorStore
+
+DIMENSION = 64
+
+
+def record(record_id: str, text: str, **metadata) -> PineconeRecord:
+    return PineconeRecord(
+        id=record_id,
+        embedding=HashingEmbedder(DIMENSION).embed(text),
+        text=text,
+        metadata={"text": text, **metadata},
+    )
+
+
+class SmallPages:
+    """Lists two records per page, so documents span record pages."""
+
+    def __init__(self, store):
+        self.store = store
+
+    def __getattr__(self, name):
+        return getattr(self.store, name)
+
+    def list_records(self, **kwargs):
+        return self.store.list_records(limit=2, **kwargs)
+
+
+class StoreTests:
+    """Behaviour shared by the local backends, mixed into a TestCase per store."""
+
+    def create_store(self):
+        raise NotImplementedError
+
+    def setUp(self):
+        self.store = self.create_store()
+        self.store.upsert_records(
+            [
+                record("a#chunk0", "apples and pears", category="fruit", year=2020),
+                record("a#chunk1", "more apples", category="fruit", year=2021),
+                record("b#chunk0", "carrots", category="vegetable", year=2022),
+                record("c", "cherries", category="fruit", year=2023),
+            ]
+        )
+
+    def test_list_pagination(self):
+        first = self.store.list_records(limit=3)
+        self.assertEqual(
+            [v["id"] for v in first["vectors"]], ["a#chunk0", "a#chunk1", "b#chunk0"]
+        )
+        self.assertIsNotNone(first["pagination_token"])
+
+        second = self.store.list_records(
+            limit=3, pagination_token=first["pagination_token"]
+        )
+        self.assertEqual([v["id"] for v in second["vectors"]], ["c"])
+        self.assertIsNone(second["pagination_token"])
+
+    def test_list_record_ids_follows_pages(self):
+        self.assertEqual(
+            list_record_ids(self.store, prefix="a#"), ["a#chunk0", "a#chunk1"]
+        )
+
+    def test_namespaces_are_separate(self):
+        self.store.upsert_records([record("d", "dates")], namespace="other")
+        self.assertEqual(list_record_ids(self.store, namespace="other"), ["d"])
+        self.assertNotIn("d", list_record_ids(self.store))
+
+    def test_search_filter(self):
+        matches = self.store.search_records(
+            "apples",
+            top_k=10,
+            filter={"$and": [{"category": "fruit"}, {"year": {"$gte": 2021}}]},
+        )["matches"]
+        self.assertEqual([m["id"] for m in matches], ["a#chunk1", "c"])
+
+    def test_fetch_and_delete(self):
+        vectors = self.store.fetch_records(["c", "missing"])["vectors"]
+        self.assertEqual(list(vectors), ["c"])
+        self.assertEqual(fetch_metadata(self.store, ["c"])["c"]["category"], "fruit")
+        self.store.delete_records(["a#chunk0", "c"])
+        self.assertEqual(list_record_ids(self.store), ["a#chunk1", "b#chunk0"])
+
+    def test_chunk_ids_exclude_documents_sharing_the_prefix(self):
+        self.store.upsert_records([record("a#v2#chunk0", "apple sauce")])
+        self.assertEqual(list_chunk_ids(self.store, "a"), ["a#chunk0", "a#chunk1"])
+        self.assertEqual(list_chunk_ids(self.store, "a#v2"), ["a#v2#chunk0"])
+
+
+    def test_list_documents_pages_across_record_pages(self):
+        self.store.upsert_records([record("a#chunk2", "apple pie")])
+        store = SmallPages(self.store)
+
+        pages = []
+        cursor = None
+        while True:
+            page = list_documents(store, limit=1, cursor=cursor)
+            pages.append([(d["id"], d["chunks"]) for d in page["documents"]])
+            cursor = page["cursor"]
+            if not cursor:
+                break
+
+        self.assertEqual(pages, [[("a", 3)], [("b", 1)], [("c", 1)]])
+        self.assertEqual(
+            list_documents(store, prefix="a", limit=5)["documents"],
+            [{"id": "a", "chunks": 3, "title": None}],
+        )
+
+
+class InMemoryVectorStoreTest(StoreTests, unittest.TestCase):
+    def create_store(self):
+        return InMemoryVectorStore(
+            dimension=DIMENSION, embedder=HashingEmbedder(DIMENSION)
+        )
+
+
+class SQLiteVectorStoreTest(StoreTests, unittest.TestCase):
+    def create_store(self):
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        self.path = os.path.join(directory.name, "test.db")
+        store = SQLiteVectorStore(
+            self.path, dimension=DIMENSION, embedder=HashingEmbedder(DIMENSION)
+        )
+        self.addCleanup(store.conn.close)
+        return store
+
+    def test_records_survive_reopening(self):
+        self.store.conn.close()
+        store = SQLiteVectorStore(self.path, dimension=DIMENSION)
+        self.addCleanup(store.conn.close)
+        self.assertEqual(len(list_record_ids(store)), 4)